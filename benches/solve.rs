@@ -0,0 +1,22 @@
+use std::time::Instant;
+
+use poker_solver::{poker::game::Poker, solver::solve};
+
+fn main() {
+    let game = Poker::new("data/abstraction/".to_string()).unwrap();
+
+    for n in [10000, 100000, 1000000] {
+        let start = Instant::now();
+
+        solve(n, 42, &game);
+
+        let elapsed = start.elapsed().as_secs_f64();
+
+        println!(
+            "{} iterations in {:.2}s: {:.0} iterations/s",
+            n,
+            elapsed,
+            n as f64 / elapsed
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use std::{fs::File, io::BufWriter};
+
+use poker_evaluator::Evaluator;
+use poker_indexer::Indexer;
+use rand::prelude::*;
+use rayon::prelude::*;
+use smallvec::smallvec;
+
+use crate::{
+    error::{Error, Result},
+    poker::{
+        cards,
+        config::PokerConfig,
+        game::{evaluator, Variant},
+    },
+};
+
+use super::{
+    features,
+    kmeans::{kmeans, Metric},
+};
+
+// Equity describes flop and turn hands by a histogram of their river equity
+// over sampled runouts, Potential by the histogram of next street clusters
+// they can reach, grouped into bins of neighbouring strength, compared with
+// earth mover's distance. River hands are
+// always described by their equity alone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Features {
+    Equity,
+    Potential,
+}
+
+pub struct Builder {
+    pub variant: Variant,
+    pub evaluator: Evaluator,
+    pub features: Features,
+
+    pub shapes: Vec<Vec<usize>>,
+    pub clusters: Vec<usize>,
+
+    pub bins: usize,
+    pub samples: usize,
+    pub opponents: usize,
+    pub iterations: usize,
+    pub seed: u64,
+}
+
+impl Builder {
+    pub fn new(config: &PokerConfig) -> Result<Self> {
+        Ok(Self {
+            variant: config.variant,
+            evaluator: evaluator(config.evaluator.clone())?,
+            features: Features::Equity,
+
+            shapes: config.shapes.clone(),
+            clusters: config.clusters.clone(),
+
+            bins: 8,
+            samples: 32,
+            opponents: 32,
+            iterations: 100,
+            seed: 42,
+        })
+    }
+
+    pub fn indexer(&self, r: usize) -> Indexer {
+        Indexer::new(self.shapes[r].iter().map(|&x| x as _).collect())
+    }
+
+    // the canonical hands of a street as (index, cards, board), skipping the
+    // ones that use cards outside the deck of the variant
+    pub fn hands(&self, r: usize) -> Vec<(usize, u64, u64)> {
+        let indexer = self.indexer(r);
+        let round = self.shapes[r].len() - 1;
+        let out = cards::DECK & !self.variant.deck();
+
+        (0..indexer.count[round] as usize)
+            .into_par_iter()
+            .filter_map(|i| {
+                let x = indexer.unindex(i as _, round);
+                let (cards, board) = (x[0], x[1]);
+
+                if (cards | board) & out == 0 {
+                    Some((i, cards, board))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    pub fn features(&self, cards: u64, board: u64, rng: &mut impl Rng) -> Vec<f32> {
+        if board.count_ones() == 5 {
+            let e = features::equity(
+                self.variant,
+                &self.evaluator,
+                cards,
+                board,
+                self.opponents * self.samples,
+                rng,
+            );
+
+            return vec![e as f32];
+        }
+
+        self.histogram(cards, board, rng)
+    }
+
+    // the distribution of river equity over the runouts of the board
+    pub fn histogram(&self, cards: u64, board: u64, rng: &mut impl Rng) -> Vec<f32> {
+        let mut res = vec![0.0; self.bins];
+
+        for _ in 0..self.samples {
+            let runout = features::draw(self.variant, cards | board, 5 - board.count_ones(), rng);
+
+            let e = features::equity(
+                self.variant,
+                &self.evaluator,
+                cards,
+                board | runout,
+                self.opponents,
+                rng,
+            );
+
+            res[((e * self.bins as f64) as usize).min(self.bins - 1)] += 1.0 / self.samples as f32;
+        }
+
+        res
+    }
+
+    // the next street clusters reached by every card that can come, where
+    // next holds the table of street r + 1, in bins of clusters of close
+    // strength as a dense histogram over every cluster would not fit in memory
+    pub fn potential(
+        &self,
+        r: usize,
+        cards: u64,
+        board: u64,
+        indexer: &Indexer,
+        next: &[u16],
+    ) -> Vec<f32> {
+        let dead = cards | board | cards::DECK & !self.variant.deck();
+
+        let runouts = (0..52)
+            .map(|card| 1 << card)
+            .filter(|x| dead & x == 0)
+            .collect::<Vec<u64>>();
+
+        let mut res = vec![0.0; self.bins];
+        for x in &runouts {
+            let i = indexer.index(smallvec![cards, board | x]) as usize;
+            let bin = next[i] as usize * self.bins / self.clusters[r + 1];
+
+            res[bin.min(self.bins - 1)] += 1.0 / runouts.len() as f32;
+        }
+
+        res
+    }
+
+    // the cluster of every canonical hand of street r, in the layout read by
+    // Clusters, with clusters numbered from weakest to strongest so that the
+    // histograms of the street before are over ordered buckets
+    pub fn build(&self, r: usize, next: Option<&[u16]>) -> Vec<u16> {
+        let hands = self.hands(r);
+        let count = self.indexer(r).count[self.shapes[r].len() - 1] as usize;
+
+        // no hand of the street is dealt from this deck
+        if hands.is_empty() {
+            return vec![0; count];
+        }
+
+        let (metric, data) = match next {
+            Some(next) => {
+                let indexer = self.indexer(r + 1);
+
+                let data = hands
+                    .par_iter()
+                    .flat_map_iter(|&(_, cards, board)| {
+                        self.potential(r, cards, board, &indexer, next)
+                    })
+                    .collect::<Vec<f32>>();
+
+                (Metric::Emd, data)
+            }
+            None => {
+                let data = hands
+                    .par_iter()
+                    .flat_map_iter(|&(i, cards, board)| {
+                        let mut rng = SmallRng::seed_from_u64(self.seed ^ i as u64);
+
+                        self.features(cards, board, &mut rng)
+                    })
+                    .collect::<Vec<f32>>();
+
+                (Metric::L2, data)
+            }
+        };
+
+        let dim = data.len() / hands.len();
+        let k = self.clusters[r];
+
+        let labels = kmeans(&data, dim, k, self.iterations, self.seed, metric);
+
+        let mut strength = vec![(0.0, 0); k];
+        for (x, &label) in data.chunks(dim).zip(&labels) {
+            let s = if dim == 1 {
+                x[0] as f64
+            } else {
+                x.iter()
+                    .enumerate()
+                    .map(|(i, &p)| i as f64 * p as f64)
+                    .sum()
+            };
+
+            strength[label as usize].0 += s;
+            strength[label as usize].1 += 1;
+        }
+
+        let mut order = (0..k).collect::<Vec<usize>>();
+        order.sort_by(|&a, &b| {
+            let mean = |c: usize| strength[c].0 / strength[c].1.max(1) as f64;
+
+            mean(a).total_cmp(&mean(b))
+        });
+
+        let mut rank = vec![0; k];
+        for (i, &c) in order.iter().enumerate() {
+            rank[c] = i as u16;
+        }
+
+        let mut table = vec![0; count];
+        for (&(i, _, _), label) in hands.iter().zip(labels) {
+            table[i] = rank[label as usize];
+        }
+
+        table
+    }
+
+    // the tables of the given streets, left empty for the others, built from
+    // the river back as potential-aware features need every later street
+    pub fn tables(&self, streets: &[usize]) -> Vec<Vec<u16>> {
+        let mut tables = vec![Vec::new(); 4];
+
+        for r in (1..4).rev() {
+            let potential = self.features == Features::Potential;
+
+            let needed = streets.contains(&r) || potential && streets.iter().any(|&s| s < r);
+            if !needed {
+                continue;
+            }
+
+            let next = match self.features {
+                Features::Potential if r < 3 => Some(tables[r + 1].as_slice()),
+                _ => None,
+            };
+
+            let table = self.build(r, next);
+            tables[r] = table;
+        }
+
+        tables
+    }
+}
+
+pub fn write(path: &str, table: &[u16]) -> Result<()> {
+    let file = File::create(path).map_err(|e| Error::Io(path.to_string(), e))?;
+
+    bincode::serialize_into(BufWriter::new(file), table)
+        .map_err(|e| Error::Bincode(path.to_string(), e))
+}
@@ -0,0 +1,46 @@
+use std::cmp::Ordering;
+
+use poker_evaluator::Evaluator;
+use rand::prelude::*;
+
+use crate::poker::{cards, game::Variant};
+
+// n cards drawn from the deck of the variant, avoiding the dead ones
+pub fn draw(variant: Variant, dead: u64, n: u32, rng: &mut impl Rng) -> u64 {
+    let dead = dead | cards::DECK & !variant.deck();
+
+    let mut res: u64 = 0;
+    while res.count_ones() < n {
+        let x = 1 << rng.gen_range(0..52);
+
+        if (dead | res) & x == 0 {
+            res |= x;
+        }
+    }
+
+    res
+}
+
+// the share of the pot won against random hands on a full board
+pub fn equity(
+    variant: Variant,
+    evaluator: &Evaluator,
+    cards: u64,
+    board: u64,
+    opponents: usize,
+    rng: &mut impl Rng,
+) -> f64 {
+    let mut sum = 0.0;
+
+    for _ in 0..opponents {
+        let other = draw(variant, cards | board, variant.hole(), rng);
+
+        sum += match variant.compare(evaluator, [cards, other], board) {
+            Ordering::Less => 1.0,
+            Ordering::Equal => 0.5,
+            Ordering::Greater => 0.0,
+        };
+    }
+
+    sum / opponents as f64
+}
@@ -0,0 +1,104 @@
+use rand::{prelude::*, seq::index};
+use rayon::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    L2,
+    Emd,
+}
+
+impl Metric {
+    pub fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        match self {
+            Metric::L2 => l2(a, b),
+            Metric::Emd => emd(a, b),
+        }
+    }
+}
+
+pub fn l2(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+// the earth mover's distance between histograms over ordered buckets, which
+// is the mass carried across each gap between neighbouring buckets
+pub fn emd(a: &[f32], b: &[f32]) -> f32 {
+    let mut carry = 0.0;
+
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| {
+            carry += x - y;
+
+            f32::abs(carry)
+        })
+        .sum()
+}
+
+pub fn nearest(x: &[f32], centers: &[f32], dim: usize, metric: Metric) -> usize {
+    centers
+        .chunks(dim)
+        .map(|center| metric.distance(x, center))
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap()
+        .0
+}
+
+// data holds one point of dim features after another, the centers start at
+// k distinct points and the labels are returned once they stop changing
+pub fn kmeans(
+    data: &[f32],
+    dim: usize,
+    k: usize,
+    iterations: usize,
+    seed: u64,
+    metric: Metric,
+) -> Vec<u16> {
+    let n = data.len() / dim;
+    let k = k.min(n);
+
+    let mut rng = SmallRng::seed_from_u64(seed);
+
+    let mut centers = index::sample(&mut rng, n, k)
+        .into_iter()
+        .flat_map(|i| data[i * dim..(i + 1) * dim].to_vec())
+        .collect::<Vec<f32>>();
+
+    let mut labels = vec![u16::MAX; n];
+
+    for _ in 0..iterations {
+        let next = data
+            .par_chunks(dim)
+            .map(|x| nearest(x, &centers, dim, metric) as u16)
+            .collect::<Vec<u16>>();
+
+        if next == labels {
+            break;
+        }
+        labels = next;
+
+        let mut sums = vec![0.0; k * dim];
+        let mut counts = vec![0; k];
+
+        for (x, &label) in data.chunks(dim).zip(&labels) {
+            let c = label as usize;
+
+            counts[c] += 1;
+            for j in 0..dim {
+                sums[c * dim + j] += x[j] as f64;
+            }
+        }
+
+        // empty clusters keep their last center
+        for c in 0..k {
+            if counts[c] > 0 {
+                for j in 0..dim {
+                    centers[c * dim + j] = (sums[c * dim + j] / counts[c] as f64) as f32;
+                }
+            }
+        }
+    }
+
+    labels
+}
@@ -0,0 +1,4 @@
+pub mod builder;
+pub mod features;
+pub mod kmeans;
+pub mod report;
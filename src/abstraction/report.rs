@@ -0,0 +1,410 @@
+use poker_evaluator::Evaluator;
+use rand::prelude::*;
+use rayon::prelude::*;
+use smallvec::smallvec;
+
+use crate::{
+    interfaces::game::Game,
+    poker::{
+        cards,
+        game::{display, payoff, Node, Rake, State, Variant},
+        nolimit::{NoLimit, Table},
+        range::{self, COMBOS},
+    },
+    solver::{normalize, Infoset},
+};
+
+use super::{builder::Builder, features};
+
+pub struct Street {
+    pub sizes: Vec<usize>,
+
+    pub within: f64,
+    pub total: f64,
+
+    pub examples: Vec<Vec<(u64, u64)>>,
+}
+
+impl Street {
+    // the share of equity variance the buckets do not explain
+    pub fn unexplained(&self) -> f64 {
+        self.within / self.total
+    }
+}
+
+// the expected river equity of a hand, averaged over sampled runouts
+fn strength(builder: &Builder, cards: u64, board: u64, rng: &mut impl Rng) -> f64 {
+    let n = 5 - board.count_ones();
+
+    let mut sum = 0.0;
+    for _ in 0..builder.samples {
+        let runout = features::draw(builder.variant, cards | board, n, rng);
+
+        sum += features::equity(
+            builder.variant,
+            &builder.evaluator,
+            cards,
+            board | runout,
+            builder.opponents,
+            rng,
+        );
+    }
+
+    sum / builder.samples as f64
+}
+
+// bucket sizes count the canonical hands the deck of the variant deals, while
+// the variance and examples come from a uniform sample of canonical indices
+pub fn street(builder: &Builder, r: usize, table: &[u16], samples: usize, seed: u64) -> Street {
+    let k = builder.clusters[r];
+
+    let indexer = builder.indexer(r);
+    let round = builder.shapes[r].len() - 1;
+    let out = cards::DECK & !builder.variant.deck();
+
+    let sizes = (0..table.len())
+        .into_par_iter()
+        .filter(|&i| {
+            if out == 0 {
+                return true;
+            }
+
+            let x = indexer.unindex(i as _, round);
+            (x[0] | x[1]) & out == 0
+        })
+        .fold(
+            || vec![0; k],
+            |mut acc, i| {
+                acc[table[i] as usize] += 1;
+                acc
+            },
+        )
+        .reduce(
+            || vec![0; k],
+            |a, b| a.iter().zip(b).map(|(x, y)| x + y).collect(),
+        );
+
+    let sample = (0..samples)
+        .into_par_iter()
+        .filter_map(|j| {
+            let mut rng = SmallRng::seed_from_u64(seed ^ j as u64);
+
+            let i = rng.gen_range(0..table.len());
+            let x = indexer.unindex(i as _, round);
+            let (cards, board) = (x[0], x[1]);
+
+            if (cards | board) & out != 0 {
+                return None;
+            }
+
+            Some((
+                table[i] as usize,
+                cards,
+                board,
+                strength(builder, cards, board, &mut rng),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    let n = sample.len() as f64;
+    let mean = sample.iter().map(|x| x.3).sum::<f64>() / n;
+
+    let mut sums = vec![(0.0, 0); k];
+    for &(bucket, _, _, e) in &sample {
+        sums[bucket].0 += e;
+        sums[bucket].1 += 1;
+    }
+
+    let mut within = 0.0;
+    let mut total = 0.0;
+    for &(bucket, _, _, e) in &sample {
+        let center = sums[bucket].0 / sums[bucket].1 as f64;
+
+        within += (e - center) * (e - center) / n;
+        total += (e - mean) * (e - mean) / n;
+    }
+
+    let mut examples = vec![Vec::new(); k];
+    for &(bucket, cards, board, _) in &sample {
+        if examples[bucket].len() < 3 {
+            examples[bucket].push((cards, board));
+        }
+    }
+
+    Street {
+        sizes,
+        within,
+        total,
+        examples,
+    }
+}
+
+// A river subgame on a single board where every hand the deck of the variant
+// deals is bucketed through buckets, indexed by combo.
+pub struct River {
+    evaluator: Evaluator,
+    variant: Variant,
+
+    board: u64,
+    hands: Vec<u64>,
+    strength: Vec<usize>,
+    buckets: Vec<usize>,
+
+    nodes: Vec<Node>,
+}
+
+impl River {
+    pub fn new(evaluator: Evaluator, variant: Variant, board: u64, buckets: Vec<usize>) -> Self {
+        let dead = board | cards::DECK & !variant.deck();
+
+        let hands = (0..COMBOS)
+            .filter(|&i| range::combo(i) & dead == 0)
+            .collect::<Vec<usize>>();
+
+        let compare = |a: usize, b: usize| {
+            variant.compare(&evaluator, [range::combo(a), range::combo(b)], board)
+        };
+
+        let mut order = hands.clone();
+        order.sort_by(|&a, &b| compare(a, b));
+
+        // equal hands share a strength, lower is better
+        let mut strength = vec![0; COMBOS];
+        for j in 1..order.len() {
+            let rank = strength[order[j - 1]];
+
+            strength[order[j]] = if compare(order[j], order[j - 1]).is_eq() {
+                rank
+            } else {
+                rank + 1
+            };
+        }
+
+        Self {
+            evaluator,
+            variant,
+
+            board,
+            hands: hands.into_iter().map(range::combo).collect(),
+            strength,
+            buckets,
+
+            nodes: NoLimit::new(Table::default()).build_from(3, [10000, 10000]),
+        }
+        .indexed()
+    }
+
+    pub fn lossless(evaluator: Evaluator, variant: Variant, board: u64) -> Self {
+        Self::new(evaluator, variant, board, (0..COMBOS).collect())
+    }
+
+    // buckets from a river table and the indexer it was built with
+    pub fn clustered(evaluator: Evaluator, board: u64, builder: &Builder, table: &[u16]) -> Self {
+        let indexer = builder.indexer(3);
+        let dead = board | cards::DECK & !builder.variant.deck();
+
+        let buckets = (0..COMBOS)
+            .map(|i| {
+                let cards = range::combo(i);
+
+                if cards & dead == 0 {
+                    table[indexer.index(smallvec![cards, board]) as usize] as usize
+                } else {
+                    0
+                }
+            })
+            .collect();
+
+        Self::new(evaluator, builder.variant, board, buckets)
+    }
+
+    fn indexed(mut self) -> Self {
+        let n = self.buckets.iter().max().unwrap() + 1;
+
+        let mut index = 0;
+        for node in &mut self.nodes {
+            if !node.c.is_empty() {
+                node.i = index;
+
+                index += n;
+            }
+        }
+
+        self
+    }
+
+    fn deal(&self, cards: [u64; 2]) -> State {
+        let mut state = State::from(cards, [0, 0, 0, self.board]);
+
+        let [a, b] = cards.map(range::index);
+        state.showdown = Some(self.strength[a].cmp(&self.strength[b]));
+
+        state
+    }
+
+    // the value of a best response of player to the average strategies,
+    // summed over the hands the opponent can hold with their reach
+    fn best(
+        &self,
+        node: &Node,
+        player: usize,
+        cards: u64,
+        hands: &[u64],
+        reach: Vec<f64>,
+        strategies: &[Vec<f64>],
+    ) -> f64 {
+        if node.c.is_empty() {
+            return hands
+                .iter()
+                .zip(&reach)
+                .filter(|(_, &r)| r > 0.0)
+                .map(|(&other, r)| {
+                    let mut deal = [cards, other];
+                    if player == 1 {
+                        deal.swap(0, 1);
+                    }
+
+                    r * self.utility(node, &self.deal(deal), player)
+                })
+                .sum();
+        }
+
+        if node.t as usize == player {
+            return (0..node.c.len())
+                .map(|a| {
+                    self.best(
+                        self.play(node, a),
+                        player,
+                        cards,
+                        hands,
+                        reach.clone(),
+                        strategies,
+                    )
+                })
+                .max_by(f64::total_cmp)
+                .unwrap();
+        }
+
+        (0..node.c.len())
+            .map(|a| {
+                let reach = hands
+                    .iter()
+                    .zip(&reach)
+                    .map(|(&other, &r)| {
+                        if r == 0.0 {
+                            return 0.0;
+                        }
+
+                        r * strategies[node.i + self.buckets[range::index(other)]][a]
+                    })
+                    .collect();
+
+                self.best(self.play(node, a), player, cards, hands, reach, strategies)
+            })
+            .sum()
+    }
+
+    // the average of both best response values per hand dealt, which is zero
+    // exactly at an equilibrium of the unabstracted river
+    pub fn exploitability(&self, infosets: &[Infoset]) -> f64 {
+        let strategies = infosets
+            .iter()
+            .map(|infoset| normalize(infoset.s.clone()))
+            .collect::<Vec<Vec<f64>>>();
+
+        let hands = &self.hands;
+
+        let pairs = hands
+            .iter()
+            .map(|&a| hands.iter().filter(|&&b| a & b == 0).count())
+            .sum::<usize>();
+
+        let value = (0..2)
+            .map(|player| {
+                hands
+                    .par_iter()
+                    .map(|&cards| {
+                        let reach = hands
+                            .iter()
+                            .map(|&other| if cards & other == 0 { 1.0 } else { 0.0 })
+                            .collect();
+
+                        self.best(self.root(), player, cards, hands, reach, &strategies)
+                    })
+                    .sum::<f64>()
+            })
+            .sum::<f64>();
+
+        value / pairs as f64 / 2.0
+    }
+}
+
+impl Game<Node, State> for River {
+    fn done(&self, node: &Node) -> bool {
+        node.c.is_empty()
+    }
+
+    fn turn(&self, node: &Node) -> usize {
+        node.t as usize
+    }
+
+    fn next(&self, node: &Node) -> usize {
+        node.c.len()
+    }
+
+    fn init(&self, rng: &mut impl Rng) -> State {
+        loop {
+            let a = *self.hands.choose(rng).unwrap();
+            let b = *self.hands.choose(rng).unwrap();
+
+            if a & b == 0 {
+                return self.deal([a, b]);
+            }
+        }
+    }
+
+    fn root(&self) -> &Node {
+        self.nodes.last().unwrap()
+    }
+
+    fn size(&self) -> Vec<usize> {
+        let n = self.buckets.iter().max().unwrap() + 1;
+
+        let mut answer = Vec::new();
+
+        for node in &self.nodes {
+            if !node.c.is_empty() {
+                answer.resize(node.i + n, node.c.len());
+            }
+        }
+
+        answer
+    }
+
+    fn eval(&self, node: &Node, state: &State) -> f64 {
+        self.utility(node, state, 0)
+    }
+
+    fn utility(&self, node: &Node, state: &State, player: usize) -> f64 {
+        payoff(
+            &self.evaluator,
+            self.variant,
+            &Rake::default(),
+            node,
+            state,
+            player,
+        )
+    }
+
+    fn play(&self, node: &Node, action: usize) -> &Node {
+        &self.nodes[node.c[action]]
+    }
+
+    fn index(&self, node: &Node, state: &State) -> usize {
+        node.i + self.buckets[range::index(state.cards[node.t as usize])]
+    }
+
+    fn display(&self, node: &Node, state: &State) -> String {
+        display(node, state)
+    }
+}
@@ -0,0 +1,57 @@
+use std::time::Instant;
+
+use poker_solver::{
+    abstraction::builder::{write, Builder, Features},
+    error::Result,
+    poker::config::PokerConfig,
+};
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{}", e);
+
+        std::process::exit(1);
+    }
+}
+
+// writes every cluster table named in the config, which defaults to the
+// layout of data/abstraction/, with --potential for potential-aware flop and
+// turn clusters
+fn run() -> Result<()> {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+
+    let config = match args.iter().find(|x| !x.starts_with("--")) {
+        Some(path) => PokerConfig::load(path)?,
+        None => PokerConfig::new("data/abstraction/".to_string()),
+    };
+
+    let mut builder = Builder::new(&config)?;
+    if args.iter().any(|x| x == "--potential") {
+        builder.features = Features::Potential;
+    }
+
+    let start = Instant::now();
+
+    let streets = (1..4)
+        .filter(|&r| config.tables[r].is_some())
+        .collect::<Vec<usize>>();
+
+    let tables = builder.tables(&streets);
+
+    for (r, path) in config.tables.iter().enumerate().skip(1) {
+        if let Some(path) = path {
+            write(path, &tables[r])?;
+
+            println!(
+                "street {}: {} hands into {} clusters",
+                r,
+                tables[r].len(),
+                config.clusters[r]
+            );
+        }
+    }
+
+    println!("Elapsed: {:?}", start.elapsed());
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use rand::prelude::*;
+
+use poker_solver::{
+    abstraction::{
+        builder::Builder,
+        report::{self, River},
+    },
+    error::Result,
+    poker::{
+        cards,
+        config::PokerConfig,
+        game::{evaluator, table},
+    },
+    solver::solve,
+};
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{}", e);
+
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
+    let config = match std::env::args().nth(1) {
+        Some(path) => PokerConfig::load(&path)?,
+        None => PokerConfig::new("data/abstraction/".to_string()),
+    };
+
+    let builder = Builder::new(&config)?;
+
+    let mut tables = vec![None; 4];
+
+    for (r, path) in config.tables.iter().enumerate().skip(1) {
+        let Some(path) = path else {
+            continue;
+        };
+
+        let data = table(path.clone())?;
+        let street = report::street(&builder, r, &data, 10000, 42);
+
+        let mut sizes = street.sizes.clone();
+        sizes.sort_unstable();
+
+        println!("street {} ({}):", r, path);
+        println!(
+            "  bucket sizes: min {}, median {}, max {}, empty {}",
+            sizes[0],
+            sizes[sizes.len() / 2],
+            sizes[sizes.len() - 1],
+            sizes.iter().filter(|&&x| x == 0).count()
+        );
+        println!(
+            "  equity variance: {:.5} within buckets of {:.5} ({:.1}% unexplained)",
+            street.within,
+            street.total,
+            100.0 * street.unexplained()
+        );
+
+        let k = street.examples.len();
+        for bucket in (0..k).step_by((k / 8).max(1)) {
+            let hands = street.examples[bucket]
+                .iter()
+                .map(|&(x, board)| {
+                    format!("{} {}", cards::format(x, true), cards::format(board, true))
+                })
+                .collect::<Vec<String>>();
+
+            println!("  bucket {}: {}", bucket, hands.join(", "));
+        }
+
+        tables[r] = Some(data);
+    }
+
+    if let Some(data) = &tables[3] {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let board = (0..52)
+            .filter(|&x| config.variant.deck() >> x & 1 != 0)
+            .collect::<Vec<u64>>()
+            .choose_multiple(&mut rng, 5)
+            .fold(0, |acc, x| acc | 1 << x);
+
+        let lossless = River::lossless(evaluator(config.evaluator.clone())?, config.variant, board);
+        let clustered =
+            River::clustered(evaluator(config.evaluator.clone())?, board, &builder, data);
+
+        println!("river exploitability on {}:", cards::format(board, true));
+        for (name, game) in [("lossless", &lossless), ("clustered", &clustered)] {
+            let infosets = solve(1000000, 42, game);
+
+            println!(
+                "  {}: {:.2} chips per hand",
+                name,
+                game.exploitability(&infosets)
+            );
+        }
+    }
+
+    Ok(())
+}
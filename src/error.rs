@@ -0,0 +1,69 @@
+use std::{fmt, fs::File, io::BufReader, path::Path};
+
+#[derive(Debug)]
+pub enum Error {
+    Missing(String),
+    Io(String, std::io::Error),
+    Json(String, serde_json::Error),
+    Bincode(String, bincode::Error),
+    Corrupt(String),
+    Notation(String),
+    Deal(String),
+    Config(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Missing(path) => write!(f, "missing file: {}", path),
+            Error::Io(path, e) => write!(f, "cannot read {}: {}", path, e),
+            Error::Json(path, e) => write!(f, "corrupt json in {}: {}", path, e),
+            Error::Bincode(path, e) => write!(f, "corrupt data in {}: {}", path, e),
+            Error::Corrupt(path) => write!(f, "corrupt file: {}", path),
+            Error::Notation(e) => write!(f, "invalid notation: {}", e),
+            Error::Deal(e) => write!(f, "invalid deal: {}", e),
+            Error::Config(e) => write!(f, "invalid config: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(_, e) => Some(e),
+            Error::Json(_, e) => Some(e),
+            Error::Bincode(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+pub fn exists(path: &str) -> Result<()> {
+    if Path::new(path).exists() {
+        Ok(())
+    } else {
+        Err(Error::Missing(path.to_string()))
+    }
+}
+
+pub fn size(path: &str) -> Result<u64> {
+    exists(path)?;
+
+    std::fs::metadata(path)
+        .map(|metadata| metadata.len())
+        .map_err(|e| Error::Io(path.to_string(), e))
+}
+
+pub fn open(path: &str) -> Result<BufReader<File>> {
+    exists(path)?;
+
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|e| Error::Io(path.to_string(), e))
+}
+
+pub fn json<T: serde::de::DeserializeOwned>(path: &str) -> Result<T> {
+    serde_json::from_reader(open(path)?).map_err(|e| Error::Json(path.to_string(), e))
+}
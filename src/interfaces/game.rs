@@ -13,8 +13,22 @@ pub trait Game<Node, State> {
 
     fn size(&self) -> Vec<usize>;
 
+    fn players(&self) -> usize {
+        2
+    }
+
     fn eval(&self, node: &Node, state: &State) -> f64;
 
+    fn utility(&self, node: &Node, state: &State, player: usize) -> f64 {
+        let u = self.eval(node, state);
+
+        if player == 0 {
+            u
+        } else {
+            -u
+        }
+    }
+
     fn play(&self, node: &Node, action: usize) -> &Node;
 
     fn index(&self, node: &Node, state: &State) -> usize;
@@ -1,3 +1,5 @@
+pub mod abstraction;
+pub mod error;
 pub mod interfaces;
 pub mod poker;
-pub mod solver;
\ No newline at end of file
+pub mod solver;
@@ -1,156 +1,65 @@
-use std::io::{Read, Write};
-
-use colored::*;
-
-use poker_indexer::Indexer;
 use poker_solver::{
-    interfaces::game::Game,
-    poker::game::{Poker, State},
-    solver::{normalize, solve, Infoset},
+    error::Result,
+    poker::{
+        chart::chart,
+        game::Poker,
+        query::{query, walk},
+    },
+    solver::{load, save, solve},
 };
 
 fn main() {
-    let game = Poker::new("data/abstraction/".to_string());
-
-    let start = std::time::Instant::now();
-
-    let data = solve(2000000000, 420, &game);
-
-    println!("Elapsed: {:?}", start.elapsed());
-
-    let buffer = bincode::serialize(&data).unwrap();
-    std::fs::File::create("data/solution.bin".to_string())
-        .unwrap()
-        .write_all(&buffer)
-        .unwrap();
-
-    // let mut buffer = Vec::new();
-
-    // std::fs::File::open("data/solution.bin".to_string())
-    //     .unwrap()
-    //     .read_to_end(&mut buffer)
-    //     .unwrap();
-
-    // let infosets: Vec<Infoset> = bincode::deserialize(&buffer).unwrap();
-
-    // let mut board = [0, 1 << 6 | 1 << 3 | 1 << 34, 1 << 8, 1 << 39];
-    // for i in 1..4 {
-    //     board[i] |= board[i - 1];
-    // }
-
-    // let state = State::from([1 << 47 | 1 << 21, 1 << 12 | 1 << 1], board);
+    if let Err(e) = run() {
+        eprintln!("{}", e);
 
-    // let actions = vec![1, 0, 0, 0, 2, 1, 2, 2];
-
-    // let mut node = game.root();
-
-    // for i in 0..actions.len() + 1 {
-    //     let index = game.index(node, &state);
-    //     let infoset = &infosets[index];
-
-    //     println!(
-    //         "{}: [{:?}]",
-    //         game.display(node, &state),
-    //         normalize(infoset.s.clone())
-    //             .into_iter()
-    //             .map(|x| format!("{:.2}", x))
-    //             .collect::<Vec<String>>()
-    //             .join(" ")
-    //     );
-
-    //     if i == actions.len() {
-    //         break;
-    //     }
-
-    //     let action = actions[i];
-
-    //     node = game.play(node, action);
-    // }
-
-    // let indexer = Indexer::new(vec![2, 3]);
-
-    // let mut node = game.root();
-    // node = game.play(node, 1);
-    // node = game.play(node, 2);
-    // // node = game.play(node, 2);
-    // // node = game.play(node, 2);
-
-    // for i in 0..169 {
-    //     let cards = indexer.unindex(i, 0);
-
-    //     let state = State::from([cards[0], cards[0]], [0, 0, 0, 0]);
+        std::process::exit(1);
+    }
+}
 
-    //     let infoset = &infosets[game.index(node, &state)];
+// solves into data/solution.bin, or reads the solution there with
+// query <history> <cards> [board], e.g. query r6000c/ AhKd 2c7d9h, or
+// chart <history> [board] [--csv | --html]
+fn run() -> Result<()> {
+    let game = Poker::new("data/abstraction/".to_string())?;
 
-    //     println!(
-    //         "{}: [{:?}]",
-    //         game.display(node, &State::from([cards[0], 0], [0, 0, 0, 0])),
-    //         normalize(infoset.s.clone())
-    //             .into_iter()
-    //             .map(|x| format!("{:.2}", x))
-    //             .collect::<Vec<String>>()
-    //             .join(" ")
-    //     );
-    // }
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
 
-    // let node = game.root();
-    // let node = game.play(node, 3);
-    // let node = game.play(node, 3);
-    // let node = game.play(node, 3);
-    // let node = game.play(node, 1);
+    if args.first().map(String::as_str) == Some("query") {
+        let get = |i: usize| args.get(i).map_or("", String::as_str);
 
-    // println!("{}: {} {:?}", node.h, node.r, node.s);
+        let infosets = load("data/solution.bin")?;
 
-    // let mut board = [0, 1 << 6 | 1 << 3 | 1 << 34, 1 << 8, 1 << 39];
-    // for i in 1..4 {
-    //     board[i] |= board[i - 1];
-    // }
+        println!("{}", query(&game, &infosets, get(1), get(2), get(3))?);
 
+        return Ok(());
+    }
 
-    // let mut matrix = vec![vec![vec![0.0; 13]; 13]; node.children.len()];
-    // for a in 0..13 {
-    //     for b in 0..13 {
-    //         let a_card = 1 << a;
-    //         let b_card = 1 << b << if a < b { 0 } else { 13 };
+    if args.first().map(String::as_str) == Some("chart") {
+        let get = |i: usize| args.get(i).map_or("", String::as_str);
 
-    //         let i = game.index(node, &State::from([a_card | b_card, a_card | b_card], board));
+        let infosets = load("data/solution.bin")?;
+        let board = if get(2).starts_with("--") { "" } else { get(2) };
 
-    //         let s = normalize(infosets[i].s.clone());
+        let chart = chart(&game, &infosets, walk(&game, get(1))?, board)?;
 
-    //         for i in 0..matrix.len() {
-    //             matrix[i][a][b] = s[i];
-    //         }
-    //     }
-    // }
+        if args.iter().any(|x| x == "--csv") {
+            print!("{}", chart.csv());
+        } else if args.iter().any(|x| x == "--html") {
+            print!("{}", chart.html());
+        } else {
+            print!("{}", chart.terminal());
+        }
 
-    // let display = |matrix: &Vec<Vec<f64>>| {
-    //     let mut res = String::new();
+        return Ok(());
+    }
 
-    //     for row in 0..13 {
-    //         for col in 0..13 {
-    //             let s = &format!("{:.2} ", matrix[row][col]);
+    let start = std::time::Instant::now();
 
-    //             if matrix[row][col] > 0.5 {
-    //                 res += &s.green().to_string();
-    //                 continue;
-    //             }
+    let data = solve(2000000000, 420, &game);
 
-    //             if matrix[row][col] < 0.1 {
-    //                 res += &s.red().to_string();
-    //                 continue;
-    //             }
+    println!("Elapsed: {:?}", start.elapsed());
 
-    //             res += s;
-    //         }
-    //         res += "\n";
-    //     }
-
-    //     res
-    // };
+    save("data/solution.bin", &data)?;
 
-    // println!("{}", game.display(node, &State::from([0, 0], board)));
-
-    // for (i, x) in matrix.into_iter().enumerate() {
-    //     println!("{:?}:\n{}", game.play(node, i).s, display(&x));
-    // }
+    Ok(())
 }
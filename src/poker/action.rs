@@ -0,0 +1,85 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+// Every amount is the total the player has put in over the hand once the
+// action is taken, antes included, so b8000 bets to 8000.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    Fold,
+    Check,
+    Call,
+    Bet(i32),
+    Raise(i32),
+    AllIn(i32),
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Action::Fold => write!(f, "f"),
+            Action::Check => write!(f, "x"),
+            Action::Call => write!(f, "c"),
+            Action::Bet(amount) => write!(f, "b{}", amount),
+            Action::Raise(to) => write!(f, "r{}", to),
+            Action::AllIn(to) => write!(f, "a{}", to),
+        }
+    }
+}
+
+pub fn format(streets: &[Vec<Action>]) -> String {
+    streets
+        .iter()
+        .map(|actions| actions.iter().map(|a| a.to_string()).collect::<String>())
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+pub fn parse(input: &str) -> Result<Vec<Vec<Action>>> {
+    let mut streets = vec![vec![]];
+
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        let action = match c {
+            'f' => Action::Fold,
+            'x' => Action::Check,
+            'c' => Action::Call,
+            'b' | 'r' | 'a' => {
+                let mut amount = String::new();
+                while let Some(&d) = chars.peek() {
+                    if !d.is_ascii_digit() {
+                        break;
+                    }
+                    amount.push(d);
+                    chars.next();
+                }
+
+                let amount = amount.parse().map_err(|_| {
+                    Error::Notation(format!("missing amount after '{}' in \"{}\"", c, input))
+                })?;
+
+                match c {
+                    'b' => Action::Bet(amount),
+                    'r' => Action::Raise(amount),
+                    _ => Action::AllIn(amount),
+                }
+            }
+            '/' => {
+                streets.push(vec![]);
+                continue;
+            }
+            _ => {
+                return Err(Error::Notation(format!(
+                    "unexpected '{}' in \"{}\"",
+                    c, input
+                )))
+            }
+        };
+
+        streets.last_mut().unwrap().push(action);
+    }
+
+    Ok(streets)
+}
@@ -0,0 +1,106 @@
+use crate::error::{Error, Result};
+
+pub const RANKS: [char; 13] = [
+    '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A',
+];
+
+pub const SUITS: [char; 4] = ['s', 'd', 'c', 'h'];
+
+pub const SYMBOLS: [&str; 4] = ["♠", "♦", "♣", "♥"];
+
+pub const DECK: u64 = (1 << 52) - 1;
+
+pub fn card(rank: usize, suit: usize) -> u64 {
+    1 << (suit * 13 + rank)
+}
+
+pub fn list(input: &str) -> Result<Vec<u64>> {
+    let chars = input
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<char>>();
+
+    if chars.len() % 2 != 0 {
+        return Err(Error::Notation(format!("incomplete card in \"{}\"", input)));
+    }
+
+    let mut used = 0;
+    let mut cards = Vec::new();
+
+    for pair in chars.chunks(2) {
+        let rank = RANKS
+            .iter()
+            .position(|&r| r == pair[0].to_ascii_uppercase());
+        let suit = SUITS
+            .iter()
+            .position(|&s| s == pair[1].to_ascii_lowercase());
+
+        let (Some(rank), Some(suit)) = (rank, suit) else {
+            return Err(Error::Notation(format!(
+                "invalid card \"{}{}\" in \"{}\"",
+                pair[0], pair[1], input
+            )));
+        };
+
+        let x = card(rank, suit);
+
+        if used & x != 0 {
+            return Err(Error::Notation(format!(
+                "duplicate card \"{}{}\" in \"{}\"",
+                pair[0], pair[1], input
+            )));
+        }
+        used |= x;
+
+        cards.push(x);
+    }
+
+    Ok(cards)
+}
+
+pub fn parse(input: &str) -> Result<u64> {
+    Ok(list(input)?.into_iter().fold(0, |acc, x| acc | x))
+}
+
+// splits a board in dealing order into the cumulative per-street layout of State
+pub fn board(input: &str) -> Result<[u64; 4]> {
+    let cards = list(input)?;
+
+    if ![0, 3, 4, 5].contains(&cards.len()) {
+        return Err(Error::Notation(format!(
+            "a board has 0, 3, 4 or 5 cards, got \"{}\"",
+            input
+        )));
+    }
+
+    let mut board = [0; 4];
+    for (r, n) in [(1, 3), (2, 4), (3, 5)] {
+        if cards.len() >= n {
+            board[r] = cards[..n].iter().fold(0, |acc, x| acc | x);
+        }
+    }
+
+    Ok(board)
+}
+
+pub fn format(cards: u64, ascii: bool) -> String {
+    let mut order = (0..52)
+        .filter(|&card| cards >> card & 1 != 0)
+        .map(|card| (card % 13, card / 13))
+        .collect::<Vec<(usize, usize)>>();
+
+    // highest rank first
+    order.sort_by(|a, b| b.cmp(a));
+
+    order
+        .into_iter()
+        .map(|(rank, suit)| {
+            if ascii {
+                format!("{}{}", RANKS[rank], SUITS[suit])
+            } else {
+                format!("{}{}", RANKS[rank], SYMBOLS[suit])
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(if ascii { "" } else { " " })
+}
@@ -0,0 +1,249 @@
+use colored::*;
+
+use crate::{
+    error::Result,
+    interfaces::game::Game,
+    solver::{normalize, Infoset},
+};
+
+use super::{
+    cards::{self, RANKS},
+    game::{Node, Poker, State, Variant},
+    query::{acting, check, labels, street},
+    range::{self, COMBOS},
+    rank, shortdeck,
+};
+
+pub const CLASSES: [&str; 9] = [
+    "high card",
+    "pair",
+    "two pair",
+    "trips",
+    "straight",
+    "flush",
+    "full house",
+    "quads",
+    "straight flush",
+];
+
+// Preflop charts hold the 169 starting hands in the usual 13x13 layout, row
+// by row from aces down, with suited hands above the diagonal and offsuit
+// ones below. Postflop charts hold the made hand classes on the board.
+// freq[a][h] is the average probability of action a over the combos of hand
+// h, and combos the number of those not blocked by the board.
+pub struct Chart {
+    pub grid: bool,
+
+    pub actions: Vec<String>,
+    pub hands: Vec<String>,
+    pub combos: Vec<usize>,
+    pub freq: Vec<Vec<f64>>,
+}
+
+// the grid cell of a two-card hand
+fn cell(cards: u64) -> usize {
+    let hi = 63 - cards.leading_zeros() as usize;
+    let lo = cards.trailing_zeros() as usize;
+
+    let (a, b) = (12 - hi % 13, 12 - lo % 13);
+    let (a, b) = (a.min(b), a.max(b));
+
+    if hi / 13 == lo / 13 {
+        a * 13 + b
+    } else {
+        b * 13 + a
+    }
+}
+
+fn name(cell: usize) -> String {
+    let (row, col) = (cell / 13, cell % 13);
+    let (a, b) = (RANKS[12 - row.min(col)], RANKS[12 - row.max(col)]);
+
+    match row.cmp(&col) {
+        std::cmp::Ordering::Equal => format!("{}{}", a, b),
+        std::cmp::Ordering::Less => format!("{}{}s", a, b),
+        std::cmp::Ordering::Greater => format!("{}{}o", a, b),
+    }
+}
+
+// the hold'em made hand class of up to seven cards, an index into CLASSES
+pub fn class(cards: u64) -> usize {
+    (rank::holdem(cards) >> 20) as usize
+}
+
+// short deck ranks flushes above full houses and plays A-6-7-8-9 straights
+fn classify(variant: Variant, cards: u64) -> usize {
+    match variant {
+        Variant::ShortDeck => {
+            [0, 1, 2, 3, 4, 6, 5, 7, 8][(shortdeck::evaluate(cards) >> 20) as usize]
+        }
+        _ => class(cards),
+    }
+}
+
+// the chart of the player to act at node, with board in card notation and
+// empty preflop
+pub fn chart(game: &Poker, infosets: &[Infoset], node: &Node, board: &str) -> Result<Chart> {
+    let variant = game.variant();
+
+    variant.two_cards("charts")?;
+
+    acting(game, node)?;
+
+    check(game, infosets)?;
+
+    street(node, board)?;
+
+    let board = cards::board(board)?;
+    let dead = board.iter().fold(0, |acc, x| acc | x) | cards::DECK & !variant.deck();
+
+    let grid = node.r == 0;
+
+    let actions = labels(game, node);
+
+    let hands = if grid {
+        (0..169).map(name).collect::<Vec<String>>()
+    } else {
+        CLASSES.iter().map(|x| x.to_string()).collect()
+    };
+
+    let mut combos = vec![0; hands.len()];
+    let mut freq = vec![vec![0.0; hands.len()]; actions.len()];
+
+    for i in 0..COMBOS {
+        let x = range::combo(i);
+
+        if x & dead != 0 {
+            continue;
+        }
+
+        let h = if grid {
+            cell(x)
+        } else {
+            classify(variant, x | board[node.r as usize])
+        };
+
+        let state = State::from([x, x], board);
+        let strategy = normalize(infosets[game.index(node, &state)].s.clone());
+
+        combos[h] += 1;
+        for (a, p) in strategy.into_iter().enumerate() {
+            freq[a][h] += p;
+        }
+    }
+
+    for row in &mut freq {
+        for (h, x) in row.iter_mut().enumerate() {
+            if combos[h] > 0 {
+                *x /= combos[h] as f64;
+            }
+        }
+    }
+
+    Ok(Chart {
+        grid,
+
+        actions,
+        hands,
+        combos,
+        freq,
+    })
+}
+
+impl Chart {
+    pub fn terminal(&self) -> String {
+        let paint = |x: f64| {
+            let s = format!("{:.2} ", x);
+
+            if x > 0.5 {
+                s.green().to_string()
+            } else if x < 0.1 {
+                s.red().to_string()
+            } else {
+                s
+            }
+        };
+
+        let mut res = String::new();
+
+        for (action, row) in self.actions.iter().zip(&self.freq) {
+            res += &format!("{}:\n", action);
+
+            if self.grid {
+                for cells in row.chunks(13) {
+                    for &x in cells {
+                        res += &paint(x);
+                    }
+                    res += "\n";
+                }
+            } else {
+                for (h, &x) in row.iter().enumerate() {
+                    if self.combos[h] > 0 {
+                        res += &format!("{:>16} {}\n", self.hands[h], paint(x));
+                    }
+                }
+            }
+        }
+
+        res
+    }
+
+    pub fn csv(&self) -> String {
+        let mut res = format!("hand,combos,{}\n", self.actions.join(","));
+
+        for (h, hand) in self.hands.iter().enumerate() {
+            res += &format!("{},{}", hand, self.combos[h]);
+
+            for row in &self.freq {
+                res += &format!(",{:.4}", row[h]);
+            }
+            res += "\n";
+        }
+
+        res
+    }
+
+    // a standalone page with one heatmap per action
+    pub fn html(&self) -> String {
+        let mut res = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<style>\n\
+             body { font-family: sans-serif; }\n\
+             table { border-collapse: collapse; margin-bottom: 1em; }\n\
+             td { border: 1px solid #ccc; padding: 4px; text-align: center; font-size: 12px; }\n\
+             </style>\n</head>\n<body>\n",
+        );
+
+        let td = |hand: &str, x: f64| {
+            format!(
+                "<td style=\"background: rgba(46, 160, 67, {:.3})\">{}<br>{:.2}</td>",
+                x, hand, x
+            )
+        };
+
+        for (action, row) in self.actions.iter().zip(&self.freq) {
+            res += &format!("<h3>{}</h3>\n<table>\n", action);
+
+            if self.grid {
+                for r in 0..13 {
+                    res += "<tr>";
+                    for c in 0..13 {
+                        res += &td(&self.hands[r * 13 + c], row[r * 13 + c]);
+                    }
+                    res += "</tr>\n";
+                }
+            } else {
+                for (h, &x) in row.iter().enumerate() {
+                    if self.combos[h] > 0 {
+                        res += &format!("<tr>{}</tr>\n", td(&self.hands[h], x));
+                    }
+                }
+            }
+
+            res += "</table>\n";
+        }
+
+        res += "</body>\n</html>\n";
+
+        res
+    }
+}
@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::{self, Result};
+
+use super::{
+    game::{Rake, Recall, Variant, CLUSTERS},
+    omaha,
+    range::Range,
+};
+
+// Streets without a cluster table are indexed losslessly, in which case the
+// cluster count has to match the number of canonical hands of that street.
+// Levels hold further abstractions a node can be keyed on instead, level 0
+// being the tables and clusters above.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PokerConfig {
+    pub tree: String,
+    pub evaluator: String,
+    pub tables: Vec<Option<String>>,
+    pub clusters: Vec<usize>,
+    pub shapes: Vec<Vec<usize>>,
+
+    #[serde(default)]
+    pub variant: Variant,
+    #[serde(default)]
+    pub recall: Recall,
+    #[serde(default)]
+    pub rake: Rake,
+    #[serde(default)]
+    pub ranges: Option<[Range; 2]>,
+    #[serde(default)]
+    pub levels: Vec<Level>,
+}
+
+// With pot set every node whose pot reaches it uses this level, otherwise
+// the level is the one recorded in the tree.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Level {
+    #[serde(default)]
+    pub pot: Option<i32>,
+    pub tables: Vec<Option<String>>,
+    pub clusters: Vec<usize>,
+}
+
+impl PokerConfig {
+    pub fn new(path: String) -> Self {
+        Self {
+            tree: path.clone() + "poker-tree.json",
+            evaluator: "data/evaluator".to_string(),
+            tables: vec![
+                None,
+                Some(path.clone() + "cluster_1.bin"),
+                Some(path.clone() + "cluster_2.bin"),
+                Some(path.clone() + "cluster_3.bin"),
+            ],
+            clusters: CLUSTERS.to_vec(),
+            shapes: vec![vec![2, 0], vec![2, 3], vec![2, 4], vec![2, 5]],
+            variant: Variant::default(),
+            recall: Recall::default(),
+            rake: Rake::default(),
+            ranges: None,
+            levels: Vec::new(),
+        }
+    }
+
+    // the cluster counts of hold'em tables assume the full deck, so short deck
+    // needs tables of its own
+    pub fn shortdeck(path: String) -> Self {
+        Self {
+            variant: Variant::ShortDeck,
+            tables: vec![
+                None,
+                Some(path.clone() + "shortdeck_cluster_1.bin"),
+                Some(path.clone() + "shortdeck_cluster_2.bin"),
+                Some(path.clone() + "shortdeck_cluster_3.bin"),
+            ],
+            ..Self::new(path)
+        }
+    }
+
+    // four-card hands are bucketed by their features, which needs no tables
+    pub fn omaha(path: String) -> Self {
+        Self {
+            variant: Variant::Omaha,
+            tables: vec![None; 4],
+            clusters: omaha::CLUSTERS.to_vec(),
+            shapes: vec![vec![4, 0], vec![4, 3], vec![4, 4], vec![4, 5]],
+            ..Self::new(path)
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        error::json(path)
+    }
+
+    // the config of every level, each with its own tables and clusters
+    pub fn levels(&self) -> Vec<PokerConfig> {
+        let mut res = vec![Self {
+            levels: Vec::new(),
+            ..self.clone()
+        }];
+
+        for level in &self.levels {
+            res.push(Self {
+                tables: level.tables.clone(),
+                clusters: level.clusters.clone(),
+                levels: Vec::new(),
+                ..self.clone()
+            });
+        }
+
+        res
+    }
+}
@@ -0,0 +1,277 @@
+use rand::{distributions::WeightedIndex, prelude::*};
+use rayon::prelude::*;
+
+use crate::{
+    error::{Error, Result},
+    interfaces::game::Game,
+    solver::{normalize, Infoset},
+};
+
+use super::{
+    action::Action,
+    cards,
+    game::{Deal, Node, Poker, State, STREETS},
+    omaha,
+    query::{acting, labels, street, walk},
+    range::{self, COMBOS},
+    reach::reach,
+};
+
+// Exact rollouts weigh every opponent hand, action and runout, which is only
+// practical late in the hand. Sampled ones average the given number of
+// playouts per hand and action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rollout {
+    Exact,
+    Sampled(usize),
+}
+
+// ev[i][a] is the value to the player to act of holding combo i and taking
+// action a, then both players following the average strategies, against the
+// hands the opponent reaches the node with. Values are net of everything the
+// player has put in, and None for combos the board blocks or that no
+// opponent hand reaching the node can face.
+pub struct Values {
+    pub player: usize,
+
+    pub actions: Vec<String>,
+    pub strategy: Vec<Option<Vec<f64>>>,
+    pub ev: Vec<Option<Vec<f64>>>,
+}
+
+impl Values {
+    // what taking action a gives up against the best action with combo i
+    pub fn loss(&self, i: usize, a: usize) -> Option<f64> {
+        let ev = self.ev[i].as_ref()?;
+
+        Some(ev.iter().copied().fold(f64::MIN, f64::max) - ev[a])
+    }
+
+    // what the average strategy gives up with combo i
+    pub fn regret(&self, i: usize) -> Option<f64> {
+        let strategy = self.strategy[i].as_ref()?;
+
+        (0..strategy.len())
+            .map(|a| Some(strategy[a] * self.loss(i, a)?))
+            .sum()
+    }
+}
+
+struct Rollouts<'a> {
+    game: &'a Poker,
+    infosets: &'a [Infoset],
+    player: usize,
+    dead: u64,
+}
+
+impl Rollouts<'_> {
+    // normalized as visited, since rollouts reach few of the infosets
+    fn strategy(&self, node: &Node, state: &State) -> Vec<f64> {
+        normalize(self.infosets[self.game.index(node, state)].s.clone())
+    }
+
+    // the expected value of node, dealing the streets board is missing
+    fn exact(&self, node: &Node, cards: [u64; 2], board: [u64; 4], known: usize) -> f64 {
+        let game = self.game;
+
+        // showdowns need the whole board, folds none of the rest
+        let street = match game.done(node) {
+            true if node.a == Some(Action::Fold) => known,
+            true => 3,
+            false => node.r as usize,
+        };
+
+        if street > known {
+            let used = cards[0] | cards[1] | board[known] | self.dead;
+            let runouts = omaha::subsets(cards::DECK & !used, STREETS[known]);
+
+            return runouts
+                .iter()
+                .map(|&x| {
+                    let mut board = board;
+                    board[known + 1] = board[known] | x;
+
+                    self.exact(node, cards, board, known + 1)
+                })
+                .sum::<f64>()
+                / runouts.len() as f64;
+        }
+
+        let state = State::from(cards, board);
+
+        if game.done(node) {
+            return game.utility(node, &state, self.player);
+        }
+
+        self.strategy(node, &state)
+            .iter()
+            .enumerate()
+            .filter(|(_, &p)| p > 0.0)
+            .map(|(a, &p)| p * self.exact(game.play(node, a), cards, board, known))
+            .sum()
+    }
+
+    fn sampled(&self, node: &Node, state: &State, rng: &mut impl Rng) -> f64 {
+        let game = self.game;
+
+        let mut node = node;
+        while !game.done(node) {
+            let strategy = self.strategy(node, state);
+            let a = WeightedIndex::new(&strategy).unwrap().sample(rng);
+
+            node = game.play(node, a);
+        }
+
+        game.utility(node, state, self.player)
+    }
+}
+
+// SplitMix64, so that the rollouts of every hand and action get their own
+// stream however close their numbers are
+fn mix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ z >> 30).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ z >> 27).wrapping_mul(0x94d049bb133111eb);
+
+    z ^ z >> 31
+}
+
+// the values of every action at the node after history, with board in card
+// notation covering at least the street of the node
+pub fn values(
+    game: &Poker,
+    infosets: &[Infoset],
+    history: &str,
+    board: &str,
+    rollout: Rollout,
+    seed: u64,
+) -> Result<Values> {
+    if rollout == Rollout::Sampled(0) {
+        return Err(Error::Config(
+            "sampled rollouts need at least one playout".to_string(),
+        ));
+    }
+
+    let node = walk(game, history)?;
+
+    acting(game, node)?;
+
+    // reach checks the solution, the variant and the streets of the board
+    let ranges = reach(game, infosets, history, board)?;
+
+    let known = street(node, board)?;
+
+    let board = cards::board(board)?;
+    let dead = cards::DECK & !game.variant().deck();
+
+    let player = node.t as usize;
+    let opponent = &ranges[player ^ 1].w;
+
+    let rollouts = Rollouts {
+        game,
+        infosets,
+        player,
+        dead,
+    };
+
+    let actions = labels(game, node);
+
+    let hands = (0..COMBOS)
+        .into_par_iter()
+        .map(|i| {
+            let hand = range::combo(i);
+
+            if hand & (board[known] | dead) != 0 {
+                return None;
+            }
+
+            let weights = (0..COMBOS)
+                .map(|j| {
+                    if range::combo(j) & hand == 0 {
+                        opponent[j]
+                    } else {
+                        0.0
+                    }
+                })
+                .collect::<Vec<f64>>();
+
+            let total = weights.iter().sum::<f64>();
+            if total == 0.0 {
+                return None;
+            }
+
+            let deal = |other: u64| {
+                let mut cards = [hand, other];
+                if player == 1 {
+                    cards.swap(0, 1);
+                }
+
+                cards
+            };
+
+            let strategy = rollouts.strategy(node, &State::from(deal(0), board));
+
+            let ev = (0..game.next(node))
+                .map(|a| {
+                    let child = game.play(node, a);
+
+                    match rollout {
+                        Rollout::Exact => {
+                            (0..COMBOS)
+                                .filter(|&j| weights[j] > 0.0)
+                                .map(|j| {
+                                    let cards = deal(range::combo(j));
+
+                                    weights[j] * rollouts.exact(child, cards, board, known)
+                                })
+                                .sum::<f64>()
+                                / total
+                        }
+                        Rollout::Sampled(n) => {
+                            let mut rng =
+                                SmallRng::seed_from_u64(mix(mix(mix(seed) ^ i as u64) ^ a as u64));
+                            let opponents = WeightedIndex::new(&weights).unwrap();
+
+                            (0..n)
+                                .map(|_| {
+                                    let cards = deal(range::combo(opponents.sample(&mut rng)));
+
+                                    let mut deal = Deal::new()
+                                        .variant(game.variant())
+                                        .cards(0, cards[0])
+                                        .cards(1, cards[1]);
+                                    for r in 1..=known {
+                                        deal = deal.street(board[r] & !board[r - 1]);
+                                    }
+
+                                    let state = deal.build(&mut rng).unwrap();
+
+                                    rollouts.sampled(child, &state, &mut rng)
+                                })
+                                .sum::<f64>()
+                                / n as f64
+                        }
+                    }
+                })
+                .collect::<Vec<f64>>();
+
+            Some((strategy, ev))
+        })
+        .collect::<Vec<_>>();
+
+    let (strategy, ev) = hands
+        .into_iter()
+        .map(|x| match x {
+            Some((strategy, ev)) => (Some(strategy), Some(ev)),
+            None => (None, None),
+        })
+        .unzip();
+
+    Ok(Values {
+        player,
+
+        actions,
+        strategy,
+        ev,
+    })
+}
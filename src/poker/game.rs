@@ -1,34 +1,195 @@
+use std::{cmp::Ordering, fmt, io::Read};
+
+use poker_evaluator::Evaluator;
+use poker_indexer::Indexer;
 use rand::prelude::*;
 use serde::{Deserialize, Serialize};
 use smallvec::smallvec;
-use std::{fs::File, io::BufReader};
 
-use poker_abstraction::tables::load;
-use poker_evaluator::Evaluator;
-use poker_indexer::Indexer;
+use crate::{
+    error::{self, Error, Result},
+    interfaces::game::Game,
+};
+
+use super::{
+    action::Action,
+    cards,
+    config::PokerConfig,
+    limit::Limit,
+    nolimit::{NoLimit, Table},
+    omaha,
+    range::{Range, Sampler},
+    shortdeck,
+};
+
+pub const CLUSTERS: [usize; 4] = [169, 2197, 2197, 2197];
+
+// perfect recall multiplies the clusters of every street, so the infosets a
+// game may span are capped well before they would be allocated
+pub const MAX_INFOSETS: usize = 1 << 30;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Variant {
+    #[default]
+    Holdem,
+    ShortDeck,
+    Omaha,
+}
+
+impl Variant {
+    pub fn deck(&self) -> u64 {
+        match self {
+            Variant::ShortDeck => shortdeck::DECK,
+            _ => cards::DECK,
+        }
+    }
+
+    pub fn hole(&self) -> u32 {
+        match self {
+            Variant::Omaha => 4,
+            _ => 2,
+        }
+    }
+
+    // ranges, charts and the like deal in two-card combos, so what needs them
+    // fails for other hands
+    pub fn two_cards(&self, what: &str) -> Result<()> {
+        if self.hole() != 2 {
+            return Err(Error::Config(format!(
+                "{} are only supported for two-card hands",
+                what
+            )));
+        }
+
+        Ok(())
+    }
 
-use crate::interfaces::game::Game;
+    // lower is better, following poker_evaluator
+    pub fn compare(&self, evaluator: &Evaluator, cards: [u64; 2], board: u64) -> Ordering {
+        let [a, b] = cards;
 
-const CLUSTERS: [usize; 4] = [169, 2197, 2197, 2197];
+        match self {
+            Variant::Holdem => evaluator
+                .evaluate(a | board)
+                .cmp(&evaluator.evaluate(b | board)),
+            Variant::ShortDeck => {
+                shortdeck::evaluate(b | board).cmp(&shortdeck::evaluate(a | board))
+            }
+            Variant::Omaha => {
+                omaha::evaluate(evaluator, a, board).cmp(&omaha::evaluate(evaluator, b, board))
+            }
+        }
+    }
+}
+
+// Imperfect recall keys an infoset on the bucket of the current street only,
+// Perfect on the buckets of every street so far.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Recall {
+    #[default]
+    Imperfect,
+    Perfect,
+}
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Node {
     pub i: usize,
     pub t: u8,
     pub r: u8,
-    pub a: char,
+    pub a: Option<Action>,
     pub h: String,
 
     pub s: [i32; 2],
+    #[serde(default)]
+    pub d: i32,
+    // the abstraction level the node is keyed on
+    #[serde(default)]
+    pub g: u8,
 
     pub c: Vec<usize>,
 }
 
+// Trees written before actions were typed hold one character per action,
+// which together with the commitments is enough to rebuild it.
+#[derive(Deserialize)]
+struct Legacy {
+    i: usize,
+    t: u8,
+    r: u8,
+    a: char,
+    s: [i32; 2],
+    c: Vec<usize>,
+}
+
+pub fn tree(path: &str) -> Result<Vec<Node>> {
+    let value: serde_json::Value = error::json(path)?;
+
+    match serde_json::from_value::<Vec<Node>>(value.clone()) {
+        Ok(nodes) => Ok(nodes),
+        Err(e) => match serde_json::from_value::<Vec<Legacy>>(value) {
+            Ok(legacy) => Ok(migrate(legacy)),
+            Err(_) => Err(Error::Json(path.to_string(), e)),
+        },
+    }
+}
+
+fn migrate(legacy: Vec<Legacy>) -> Vec<Node> {
+    let mut nodes = legacy
+        .iter()
+        .map(|x| Node {
+            i: x.i,
+            t: x.t,
+            r: x.r,
+            a: None,
+            h: String::new(),
+            s: x.s,
+            d: 0,
+            g: 0,
+            c: x.c.clone(),
+        })
+        .collect::<Vec<Node>>();
+
+    // parents come after their children, so walking back sees them first
+    for p in (0..nodes.len()).rev() {
+        let parent = nodes[p].clone();
+        let t = parent.t as usize;
+
+        for &c in &parent.c {
+            let child = &mut nodes[c];
+
+            let action = match legacy[c].a {
+                'f' => Action::Fold,
+                'c' if parent.s[t] < parent.s[t ^ 1] => Action::Call,
+                'c' => Action::Check,
+                'b' => Action::Bet(child.s[t]),
+                'a' => Action::AllIn(child.s[t]),
+                _ => Action::Raise(child.s[t]),
+            };
+
+            let next = if child.r != parent.r && child.r <= 3 {
+                "/"
+            } else {
+                ""
+            };
+
+            child.a = Some(action);
+            child.h = format!("{}{}{}", parent.h, action, next);
+        }
+    }
+
+    nodes
+}
+
+// showdown and buckets are filled in once per deal by the game that dealt it,
+// with buckets held per abstraction level, player and street
 #[derive(Clone)]
 pub struct State {
     used: u64,
-    cards: [u64; 2],
-    board: [u64; 4],
+    pub cards: [u64; 2],
+    pub board: [u64; 4],
+
+    pub showdown: Option<Ordering>,
+    pub buckets: Option<Vec<[[usize; 4]; 2]>>,
 }
 
 impl State {
@@ -37,6 +198,9 @@ impl State {
             used: 0,
             cards: [rng.gen(), rng.gen()],
             board: [0; 4],
+
+            showdown: None,
+            buckets: None,
         };
 
         state.cards[0] = state.gen(rng) | state.gen(rng);
@@ -67,44 +231,722 @@ impl State {
             used: 0,
             cards,
             board,
+
+            showdown: None,
+            buckets: None,
         }
     }
 }
 
+pub const STREETS: [u32; 3] = [3, 1, 1];
+
+#[derive(Clone, Default)]
+pub struct Deal {
+    variant: Variant,
+    cards: [u64; 2],
+    board: Vec<u64>,
+}
+
+impl Deal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(hands: [&str; 2], board: &str) -> Result<Self> {
+        let mut deal = Self::new()
+            .cards(0, cards::parse(hands[0])?)
+            .cards(1, cards::parse(hands[1])?);
+
+        let board = cards::list(board)?;
+
+        if ![0, 3, 4, 5].contains(&board.len()) {
+            return Err(Error::Deal(format!(
+                "a board has 0, 3, 4 or 5 cards, got {}",
+                board.len()
+            )));
+        }
+
+        let mut i = 0;
+        for n in STREETS {
+            let n = n as usize;
+
+            if i < board.len() {
+                deal = deal.street(board[i..i + n].iter().fold(0, |acc, x| acc | x));
+            }
+            i += n;
+        }
+
+        Ok(deal)
+    }
+
+    pub fn variant(mut self, variant: Variant) -> Self {
+        self.variant = variant;
+
+        self
+    }
+
+    pub fn cards(mut self, player: usize, cards: u64) -> Self {
+        self.cards[player] = cards;
+
+        self
+    }
+
+    pub fn street(mut self, cards: u64) -> Self {
+        self.board.push(cards);
+
+        self
+    }
+
+    // unknown hole cards are left at 0 and dealt at random
+    pub fn check(&self) -> Result<u64> {
+        let mut used = 0;
+
+        for (player, &cards) in self.cards.iter().enumerate() {
+            if cards != 0 && cards.count_ones() != self.variant.hole() {
+                return Err(Error::Deal(format!(
+                    "player {} has {} hole cards",
+                    player,
+                    cards.count_ones()
+                )));
+            }
+
+            if used & cards != 0 {
+                return Err(Error::Deal(format!(
+                    "{} is dealt twice",
+                    cards::format(used & cards, true)
+                )));
+            }
+            used |= cards;
+        }
+
+        if self.board.len() > 3 {
+            return Err(Error::Deal(format!("{} streets dealt", self.board.len())));
+        }
+
+        for (r, &cards) in self.board.iter().enumerate() {
+            if cards.count_ones() != STREETS[r] {
+                return Err(Error::Deal(format!(
+                    "street {} has {} cards instead of {}",
+                    r + 1,
+                    cards.count_ones(),
+                    STREETS[r]
+                )));
+            }
+
+            if used & cards != 0 {
+                return Err(Error::Deal(format!(
+                    "{} is dealt twice",
+                    cards::format(used & cards, true)
+                )));
+            }
+            used |= cards;
+        }
+
+        let out = cards::DECK & !self.variant.deck();
+        if used & out != 0 {
+            return Err(Error::Deal(format!(
+                "{} is not in the deck",
+                cards::format(used & out, true)
+            )));
+        }
+
+        Ok(used)
+    }
+
+    // cards outside the deck start out used so they are never dealt
+    pub fn build(&self, rng: &mut impl Rng) -> Result<State> {
+        let mut state = State {
+            used: self.check()? | cards::DECK & !self.variant.deck(),
+            cards: self.cards,
+            board: [0; 4],
+
+            showdown: None,
+            buckets: None,
+        };
+
+        for player in 0..2 {
+            if state.cards[player] == 0 {
+                state.cards[player] =
+                    (0..self.variant.hole()).fold(0, |acc, _| acc | state.gen(rng));
+            }
+        }
+
+        for r in 1..4 {
+            let cards = match self.board.get(r - 1) {
+                Some(&cards) => cards,
+                None => (0..STREETS[r - 1]).fold(0, |acc, _| acc | state.gen(rng)),
+            };
+
+            state.board[r] = state.board[r - 1] | cards;
+        }
+
+        Ok(state)
+    }
+}
+
+// Omaha hands are bucketed by their features instead, with no indexers or
+// tables behind them.
+pub struct Clusters {
+    pub recall: Recall,
+
+    variant: Variant,
+    sizes: Vec<usize>,
+    rounds: Vec<usize>,
+    indexers: Vec<Indexer>,
+    tables: Vec<Option<Vec<u16>>>,
+}
+
+impl Clusters {
+    pub fn new(config: &PokerConfig) -> Result<Self> {
+        Self::check(config)?;
+
+        if config.variant == Variant::Omaha {
+            return Ok(Self {
+                recall: config.recall,
+
+                variant: config.variant,
+                sizes: config.clusters.clone(),
+                rounds: Vec::new(),
+
+                indexers: Vec::new(),
+                tables: vec![None; 4],
+            });
+        }
+
+        let rounds = config
+            .shapes
+            .iter()
+            .map(|shape| shape.len() - 1)
+            .collect::<Vec<usize>>();
+
+        let indexers = config
+            .shapes
+            .iter()
+            .map(|shape| Indexer::new(shape.iter().map(|&x| x as _).collect()))
+            .collect::<Vec<Indexer>>();
+
+        let mut tables = Vec::new();
+        for (r, path) in config.tables.iter().enumerate() {
+            let Some(path) = path else {
+                tables.push(None);
+                continue;
+            };
+
+            let data = table(path.clone())?;
+
+            // a table built for another shape or cluster count
+            let count = indexers[r].count[rounds[r]];
+            if data.len() as u64 != count {
+                return Err(Error::Config(format!(
+                    "{} has {} entries, street {} has {} hands",
+                    path,
+                    data.len(),
+                    r,
+                    count
+                )));
+            }
+            if data.iter().any(|&x| x as usize >= config.clusters[r]) {
+                return Err(Error::Config(format!(
+                    "{} has buckets beyond the {} clusters of street {}",
+                    path, config.clusters[r], r
+                )));
+            }
+
+            // a table built for a smaller deck leaves the hands it cannot deal
+            // in bucket 0, which a sample of them has to show
+            let out = cards::DECK & !config.variant.deck();
+            if out != 0 {
+                let step = (count as usize / 100000).max(1);
+
+                let mismatch = (0..count as usize).step_by(step).any(|i| {
+                    let x = indexers[r].unindex(i as _, rounds[r]);
+
+                    (x[0] | x[1]) & out != 0 && data[i] != 0
+                });
+
+                if mismatch {
+                    return Err(Error::Config(format!(
+                        "{} was not built for the {:?} deck",
+                        path, config.variant
+                    )));
+                }
+            }
+
+            tables.push(Some(data));
+        }
+
+        Ok(Self {
+            recall: config.recall,
+
+            variant: config.variant,
+            sizes: config.clusters.clone(),
+            rounds,
+
+            indexers,
+            tables,
+        })
+    }
+
+    // the lengths and shapes of a config, checked before any table is read
+    pub fn check(config: &PokerConfig) -> Result<()> {
+        let streets = config.shapes.len();
+
+        if streets != 4 || config.tables.len() != 4 || config.clusters.len() != 4 {
+            return Err(Error::Config(format!(
+                "every street needs a table, cluster count and shape, got {}, {} and {}",
+                config.tables.len(),
+                config.clusters.len(),
+                streets
+            )));
+        }
+
+        let hole = config.variant.hole() as usize;
+        if let Some(shape) = config
+            .shapes
+            .iter()
+            .find(|shape| shape.first() != Some(&hole))
+        {
+            return Err(Error::Config(format!(
+                "shape {:?} does not start with the {} hole cards",
+                shape, hole
+            )));
+        }
+
+        if config.variant == Variant::Omaha {
+            if config.tables.iter().any(Option::is_some) || config.clusters != omaha::CLUSTERS {
+                return Err(Error::Config(format!(
+                    "omaha hands are bucketed by their features into {:?} clusters, without tables",
+                    omaha::CLUSTERS
+                )));
+            }
+
+            return Ok(());
+        }
+
+        // a lossless street keys on the canonical index itself
+        for (r, shape) in config.shapes.iter().enumerate() {
+            if config.tables[r].is_some() {
+                continue;
+            }
+
+            let count =
+                Indexer::new(shape.iter().map(|&x| x as _).collect()).count[shape.len() - 1];
+            if config.clusters[r] as u64 != count {
+                return Err(Error::Config(format!(
+                    "street {} is lossless with {} hands, not {} clusters",
+                    r, count, config.clusters[r]
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn size(&self, r: u8) -> usize {
+        self.sizes[r as usize]
+    }
+
+    // the number of infosets a node on street r spans
+    pub fn width(&self, r: u8) -> usize {
+        width(self.recall, &self.sizes, r)
+    }
+
+    pub fn key(&self, r: u8, bucket: impl Fn(u8) -> usize) -> usize {
+        match self.recall {
+            Recall::Imperfect => bucket(r),
+            Recall::Perfect => (0..=r).fold(0, |acc, j| acc * self.size(j) + bucket(j)),
+        }
+    }
+
+    // the bucket of every street up to r a key holds, None where it holds none
+    pub fn unkey(&self, r: u8, mut key: usize) -> Vec<Option<usize>> {
+        let mut res = vec![None; r as usize + 1];
+
+        match self.recall {
+            Recall::Imperfect => res[r as usize] = Some(key),
+            Recall::Perfect => {
+                for j in (0..=r).rev() {
+                    res[j as usize] = Some(key % self.size(j));
+                    key /= self.size(j);
+                }
+            }
+        }
+
+        res
+    }
+
+    // up to n canonical hands of street r in a bucket as (cards, board),
+    // skipping the ones that use any card of out
+    pub fn hands(&self, r: u8, bucket: usize, n: usize, out: u64) -> Vec<(u64, u64)> {
+        // feature buckets have no canonical hands to list, so deals are drawn
+        // until enough land in the bucket
+        if self.variant == Variant::Omaha {
+            let mut rng = SmallRng::seed_from_u64(bucket as u64);
+
+            return (0..100000)
+                .map(|_| {
+                    let state = Deal::new().variant(self.variant).build(&mut rng).unwrap();
+
+                    (state.cards[0], state.board[r as usize])
+                })
+                .filter(|&(cards, board)| {
+                    (cards | board) & out == 0 && omaha::bucket(r, cards, board) == bucket
+                })
+                .take(n)
+                .collect();
+        }
+
+        let indexer = &self.indexers[r as usize];
+        let round = self.rounds[r as usize];
+
+        let indices = match &self.tables[r as usize] {
+            Some(table) => table
+                .iter()
+                .enumerate()
+                .filter(|(_, &x)| x as usize == bucket)
+                .map(|(i, _)| i)
+                .collect(),
+            None => vec![bucket],
+        };
+
+        indices
+            .into_iter()
+            .map(|i| {
+                let x = indexer.unindex(i as _, round);
+
+                (x[0], x[1])
+            })
+            .filter(|(cards, board)| (cards | board) & out == 0)
+            .take(n)
+            .collect()
+    }
+
+    pub fn bucket(&self, r: u8, cards: u64, board: u64) -> usize {
+        if self.variant == Variant::Omaha {
+            return omaha::bucket(r, cards, board);
+        }
+
+        let index = self.indexers[r as usize].index(smallvec![cards, board]) as usize;
+
+        match &self.tables[r as usize] {
+            Some(table) => table[index] as usize,
+            None => index,
+        }
+    }
+}
+
+fn width(recall: Recall, sizes: &[usize], r: u8) -> usize {
+    match recall {
+        Recall::Imperfect => sizes[r as usize],
+        Recall::Perfect => sizes[..=r as usize]
+            .iter()
+            .fold(1, |acc, &x| acc.saturating_mul(x)),
+    }
+}
+
+// a table is a bincode Vec<u16>, its length as a u64 followed by the entries,
+// so the header has to account for the whole file
+pub fn table(path: String) -> Result<Vec<u16>> {
+    let size = error::size(&path)?;
+    let mut reader = error::open(&path)?;
+
+    let mut header = [0; 8];
+    if size < 8 || reader.read_exact(&mut header).is_err() {
+        return Err(Error::Corrupt(path));
+    }
+
+    let n = u64::from_le_bytes(header);
+    if n.checked_mul(2).and_then(|x| x.checked_add(8)) != Some(size) {
+        return Err(Error::Corrupt(path));
+    }
+
+    let mut data = vec![0; 2 * n as usize];
+    reader
+        .read_exact(&mut data)
+        .map_err(|e| Error::Io(path.clone(), e))?;
+
+    Ok(data
+        .chunks_exact(2)
+        .map(|x| u16::from_le_bytes([x[0], x[1]]))
+        .collect())
+}
+
+// the evaluator reads a format of its own, so only an empty file is caught
+pub fn evaluator(path: String) -> Result<Evaluator> {
+    if error::size(&path)? == 0 {
+        return Err(Error::Corrupt(path));
+    }
+
+    Ok(Evaluator::new(path))
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Rake {
+    pub percent: f64,
+    pub cap: Option<i32>,
+    pub no_flop_no_drop: bool,
+}
+
+impl Rake {
+    pub fn take(&self, node: &Node) -> f64 {
+        if self.no_flop_no_drop && node.r == 0 {
+            return 0.0;
+        }
+
+        let rake = (node.s[0] + node.s[1] + node.d) as f64 * self.percent / 100.0;
+
+        match self.cap {
+            Some(cap) => rake.min(cap as f64),
+            None => rake,
+        }
+    }
+}
+
+// An infoset index decoded back to its node, the buckets it is keyed on and
+// some hands of the bucket of the node's street.
+pub struct Bucket<'a> {
+    pub node: &'a Node,
+    pub buckets: Vec<Option<usize>>,
+    pub hands: Vec<(u64, u64)>,
+}
+
+impl fmt::Display for Bucket<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let buckets = self
+            .buckets
+            .iter()
+            .map(|x| x.map_or("-".to_string(), |x| x.to_string()))
+            .collect::<Vec<String>>();
+
+        write!(
+            f,
+            "{} ({}) [{}]",
+            self.node.h,
+            self.node.t,
+            buckets.join(" ")
+        )?;
+
+        for &(cards, board) in &self.hands {
+            write!(
+                f,
+                "\n  {} | {}",
+                cards::format(cards, false),
+                cards::format(board, false)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
 pub struct Poker {
     evaluator: Evaluator,
+    variant: Variant,
 
-    cluster_1: Vec<u16>,
-    cluster_2: Vec<u16>,
-    cluster_3: Vec<u16>,
+    rake: Rake,
+    stacks: Option<[i32; 2]>,
 
-    indexer_0: Indexer,
-    indexer_1: Indexer,
-    indexer_2: Indexer,
-    indexer_3: Indexer,
+    ranges: Option<[Range; 2]>,
+    sampler: Option<Sampler>,
+
+    clusters: Vec<Clusters>,
 
     nodes: Vec<Node>,
 }
 
 impl Poker {
-    pub fn new(path: String) -> Self {
-        Self {
-            nodes: serde_json::from_reader(BufReader::new(
-                File::open(path.clone() + "poker-tree.json").unwrap(),
-            ))
-            .unwrap(),
+    pub fn new(path: String) -> Result<Self> {
+        Self::from_config(PokerConfig::new(path))
+    }
 
-            evaluator: Evaluator::new("data/evaluator".to_string()),
+    pub fn from_config(config: PokerConfig) -> Result<Self> {
+        let nodes = tree(&config.tree)?;
 
-            cluster_1: load(&(path.clone() + "cluster_1.bin")),
-            cluster_2: load(&(path.clone() + "cluster_2.bin")),
-            cluster_3: load(&(path.clone() + "cluster_3.bin")),
+        Self::from_nodes(config, nodes)
+    }
 
-            indexer_0: Indexer::new(vec![2, 0]),
-            indexer_1: Indexer::new(vec![2, 3]),
-            indexer_2: Indexer::new(vec![2, 4]),
-            indexer_3: Indexer::new(vec![2, 5]),
+    pub fn from_nodes(config: PokerConfig, mut nodes: Vec<Node>) -> Result<Self> {
+        if config.ranges.is_some() {
+            config.variant.two_cards("ranges")?;
+        }
+
+        let levels = config.levels();
+        for level in &levels {
+            Clusters::check(level)?;
+        }
+
+        if config.levels.iter().any(|level| level.pot.is_some()) {
+            for node in &mut nodes {
+                let pot = node.s[0] + node.s[1] + node.d;
+
+                node.g = config
+                    .levels
+                    .iter()
+                    .rposition(|level| level.pot.is_some_and(|x| pot >= x))
+                    .map_or(0, |l| l + 1) as u8;
+            }
+        }
+
+        let mut index = 0;
+        for node in &mut nodes {
+            let Some(level) = levels.get(node.g as usize) else {
+                return Err(Error::Config(format!(
+                    "node {} uses abstraction level {} of {}",
+                    node.h,
+                    node.g,
+                    levels.len()
+                )));
+            };
+
+            if !node.c.is_empty() {
+                node.i = index;
+
+                index = index.saturating_add(width(level.recall, &level.clusters, node.r));
+
+                if index > MAX_INFOSETS {
+                    return Err(Error::Config(format!(
+                        "the tree spans more than {} infosets by node {}, use fewer clusters or imperfect recall",
+                        MAX_INFOSETS, node.h
+                    )));
+                }
+            }
         }
+
+        // sized before the tables are read, which can take long
+        let clusters = levels
+            .iter()
+            .map(Clusters::new)
+            .collect::<Result<Vec<_>>>()?;
+
+        // after the tables, so a missing abstraction is reported as such
+        let evaluator = evaluator(config.evaluator)?;
+
+        Ok(Self {
+            nodes,
+
+            evaluator,
+            variant: config.variant,
+
+            rake: config.rake,
+            stacks: None,
+
+            sampler: config
+                .ranges
+                .as_ref()
+                .map(|ranges| Sampler::new(ranges, cards::DECK & !config.variant.deck()))
+                .transpose()?,
+            ranges: config.ranges,
+
+            clusters,
+        })
+    }
+
+    pub fn with_rake(mut self, rake: Rake) -> Self {
+        self.rake = rake;
+
+        self
+    }
+
+    pub fn with_stacks(mut self, stacks: [i32; 2]) -> Self {
+        self.stacks = Some(stacks);
+
+        self
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    // the starting stacks, known when the tree was built from a table
+    pub fn stacks(&self) -> Option<[i32; 2]> {
+        self.stacks
+    }
+
+    pub fn with_ranges(mut self, ranges: [Range; 2]) -> Result<Self> {
+        self.variant.two_cards("ranges")?;
+
+        self.sampler = Some(Sampler::new(&ranges, cards::DECK & !self.variant.deck())?);
+        self.ranges = Some(ranges);
+
+        Ok(self)
+    }
+
+    // the ranges hands are dealt from, every hand alike when None
+    pub fn ranges(&self) -> Option<&[Range; 2]> {
+        self.ranges.as_ref()
+    }
+
+    pub fn subtree(&self, node: &Node) -> Vec<Node> {
+        let mut nodes = Vec::new();
+
+        self.copy(node, &mut nodes);
+
+        nodes
+    }
+
+    fn copy(&self, node: &Node, nodes: &mut Vec<Node>) -> usize {
+        let c = node
+            .c
+            .iter()
+            .map(|&c| self.copy(&self.nodes[c], nodes))
+            .collect();
+
+        nodes.push(Node { c, ..node.clone() });
+
+        nodes.len() - 1
+    }
+
+    // the node and buckets behind an infoset index, with up to n hands of the
+    // bucket of the node's street
+    pub fn explore(&self, index: usize, n: usize) -> Option<Bucket<'_>> {
+        let node = self.nodes.iter().find(|node| {
+            !node.c.is_empty()
+                && node.i <= index
+                && index < node.i + self.clusters[node.g as usize].width(node.r)
+        })?;
+
+        let clusters = &self.clusters[node.g as usize];
+        let buckets = clusters.unkey(node.r, index - node.i);
+
+        let hands = clusters.hands(
+            node.r,
+            buckets[node.r as usize].unwrap(),
+            n,
+            cards::DECK & !self.variant.deck(),
+        );
+
+        Some(Bucket {
+            node,
+            buckets,
+            hands,
+        })
+    }
+
+    pub fn limit(path: String) -> Result<Self> {
+        Self::from_nodes(PokerConfig::new(path), Limit::default().build())
+    }
+
+    pub fn nolimit(path: String, table: Table) -> Result<Self> {
+        let stacks = table.stacks;
+
+        Ok(
+            Self::from_nodes(PokerConfig::new(path), NoLimit::new(table).build())?
+                .with_stacks(stacks),
+        )
+    }
+
+    pub fn omaha(path: String, table: Table) -> Result<Self> {
+        let table = Table {
+            pot_limit: true,
+            ..table
+        };
+
+        let stacks = table.stacks;
+
+        Ok(
+            Self::from_nodes(PokerConfig::omaha(path), NoLimit::new(table).build())?
+                .with_stacks(stacks),
+        )
     }
 }
 
@@ -122,7 +964,42 @@ impl Game<Node, State> for Poker {
     }
 
     fn init(&self, rng: &mut impl Rng) -> State {
-        State::new(rng)
+        let mut state = match &self.sampler {
+            Some(sampler) => {
+                let cards = sampler.sample(rng);
+
+                Deal::new()
+                    .variant(self.variant)
+                    .cards(0, cards[0])
+                    .cards(1, cards[1])
+                    .build(rng)
+                    .unwrap()
+            }
+            None => match self.variant {
+                Variant::Holdem => State::new(rng),
+                _ => Deal::new().variant(self.variant).build(rng).unwrap(),
+            },
+        };
+
+        state.showdown = Some(
+            self.variant
+                .compare(&self.evaluator, state.cards, state.board[3]),
+        );
+
+        state.buckets = Some(
+            self.clusters
+                .iter()
+                .map(|clusters| {
+                    [0, 1].map(|player| {
+                        [0, 1, 2, 3].map(|r| {
+                            clusters.bucket(r, state.cards[player], state.board[r as usize])
+                        })
+                    })
+                })
+                .collect(),
+        );
+
+        state
     }
 
     fn root(&self) -> &Node {
@@ -135,7 +1012,7 @@ impl Game<Node, State> for Poker {
             .iter()
             .map(|node| {
                 if node.c.len() > 0 {
-                    CLUSTERS[node.r as usize]
+                    self.clusters[node.g as usize].width(node.r)
                 } else {
                     0
                 }
@@ -146,7 +1023,7 @@ impl Game<Node, State> for Poker {
 
         for node in &self.nodes {
             if node.c.len() > 0 {
-                for i in 0..CLUSTERS[node.r as usize] {
+                for i in 0..self.clusters[node.g as usize].width(node.r) {
                     answer[node.i + i] = node.c.len();
                 }
             }
@@ -156,25 +1033,18 @@ impl Game<Node, State> for Poker {
     }
 
     fn eval(&self, node: &Node, state: &State) -> f64 {
-        let me = (node.t ^ 0) as usize;
-        let op = (node.t ^ 1) as usize;
-
-        if node.a == 'f' {
-            (node.s[op] * if me == 0 { 1 } else { -1 }) as f64
-        } else {
-            let me_score = self.evaluator.evaluate(state.cards[0] | state.board[3]);
-            let op_score = self.evaluator.evaluate(state.cards[1] | state.board[3]);
-
-            if me_score < op_score {
-                return node.s[op] as f64;
-            }
-
-            if me_score > op_score {
-                return node.s[me] as f64 * -1.0;
-            }
+        payoff(&self.evaluator, self.variant, &self.rake, node, state, 0)
+    }
 
-            return 0.0;
-        }
+    fn utility(&self, node: &Node, state: &State, player: usize) -> f64 {
+        payoff(
+            &self.evaluator,
+            self.variant,
+            &self.rake,
+            node,
+            state,
+            player,
+        )
     }
 
     fn play(&self, node: &Node, action: usize) -> &Node {
@@ -182,42 +1052,71 @@ impl Game<Node, State> for Poker {
     }
 
     fn index(&self, node: &Node, state: &State) -> usize {
-        let input = smallvec![state.cards[node.t as usize], state.board[node.r as usize]];
+        let r = node.r;
+        let t = node.t as usize;
+        let g = node.g as usize;
+
+        let clusters = &self.clusters[g];
+
         node.i
-            + match node.r {
-                0 => self.indexer_0.index(input) as usize,
-                1 => self.cluster_1[self.indexer_1.index(input) as usize] as usize,
-                2 => self.cluster_2[self.indexer_2.index(input) as usize] as usize,
-                3 => self.cluster_3[self.indexer_3.index(input) as usize] as usize,
-                _ => panic!("not possible"),
-            }
+            + clusters.key(r, |j| match &state.buckets {
+                Some(buckets) => buckets[g][t][j as usize],
+                None => clusters.bucket(j, state.cards[t], state.board[j as usize]),
+            })
     }
 
     fn display(&self, node: &Node, state: &State) -> String {
-        let ranks = [
-            "2", "3", "4", "5", "6", "7", "8", "9", "T", "J", "Q", "K", "A",
-        ];
+        display(node, state)
+    }
+}
 
-        let suits = ["♠", "♦", "♣", "♥"];
+pub fn payoff(
+    evaluator: &Evaluator,
+    variant: Variant,
+    rake: &Rake,
+    node: &Node,
+    state: &State,
+    player: usize,
+) -> f64 {
+    let pot = (node.s[0] + node.s[1] + node.d) as f64 - rake.take(node);
 
-        let mut res = String::new();
+    let winner = if node.a == Some(Action::Fold) {
+        Some(node.t as usize)
+    } else {
+        let showdown = state
+            .showdown
+            .unwrap_or_else(|| variant.compare(evaluator, state.cards, state.board[3]));
 
-        let deal = [state.cards[0], state.cards[1], state.board[node.r as usize]];
-        for mut cards in deal {
-            while cards != 0 {
-                let card = 63 - cards.leading_zeros();
-                let rank = card % 13;
-                let suit = card / 13;
+        match showdown {
+            Ordering::Less => Some(0),
+            Ordering::Greater => Some(1),
+            Ordering::Equal => None,
+        }
+    };
 
-                res += &format!("{}{} ", ranks[rank as usize], suits[suit as usize]);
+    let s = node.s[player] as f64;
 
-                cards &= !(1 << card);
-            }
-            res += "| "
-        }
+    match winner {
+        Some(winner) if winner == player => pot - s,
+        Some(_) => -s,
+        None => pot / 2.0 - s,
+    }
+}
 
-        res += &format!("{} ({})", node.h, node.t);
+pub fn display(node: &Node, state: &State) -> String {
+    let mut res = String::new();
 
-        res
+    let deal = [
+        state.cards[0],
+        state.cards[1],
+        state.board[node.r.min(3) as usize],
+    ];
+    for x in deal {
+        res += &cards::format(x, false);
+        res += " | ";
     }
+
+    res += &format!("{} ({})", node.h, node.t);
+
+    res
 }
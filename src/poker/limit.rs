@@ -0,0 +1,132 @@
+use super::{action::Action, game::Node};
+
+pub struct Limit {
+    pub blinds: [i32; 2],
+    pub bets: [i32; 4],
+    pub cap: usize,
+
+    nodes: Vec<Node>,
+}
+
+impl Limit {
+    pub fn new(blinds: [i32; 2], bets: [i32; 4], cap: usize) -> Self {
+        Self {
+            blinds,
+            bets,
+            cap,
+            nodes: Vec::new(),
+        }
+    }
+
+    pub fn build(mut self) -> Vec<Node> {
+        // the big blind counts as the first bet of the preflop round
+        self.dfs(0, 0, 1, None, String::new(), self.blinds);
+
+        self.nodes
+    }
+
+    fn turn(k: usize, r: u8) -> usize {
+        if r == 0 {
+            k % 2
+        } else {
+            1 - k % 2
+        }
+    }
+
+    fn add(
+        &mut self,
+        k: usize,
+        r: u8,
+        a: Option<Action>,
+        h: String,
+        s: [i32; 2],
+        c: Vec<usize>,
+    ) -> usize {
+        self.nodes.push(Node {
+            i: 0,
+            t: Self::turn(k, r) as u8,
+            r,
+            a,
+            h,
+            s,
+            d: 0,
+            g: 0,
+            c,
+        });
+
+        self.nodes.len() - 1
+    }
+
+    fn dfs(
+        &mut self,
+        k: usize,
+        r: u8,
+        n: usize,
+        a: Option<Action>,
+        h: String,
+        s: [i32; 2],
+    ) -> usize {
+        if a == Some(Action::Fold) || r > 3 {
+            return self.add(k, r, a, h, s, vec![]);
+        }
+
+        let t = Self::turn(k, r);
+        let amount = s[t ^ 1] - s[t];
+
+        let mut children = vec![];
+
+        // fold
+        if amount > 0 {
+            let action = Action::Fold;
+            children.push(self.dfs(k + 1, r, n, Some(action), format!("{}{}", h, action), s));
+        }
+
+        // check or call
+        let mut x = s;
+        x[t] = s[t ^ 1];
+
+        let action = if amount > 0 {
+            Action::Call
+        } else {
+            Action::Check
+        };
+
+        let closed = if amount > 0 { r > 0 || k > 0 } else { k > 0 };
+
+        if closed {
+            let next = if r < 3 { "/" } else { "" };
+            children.push(self.dfs(
+                0,
+                r + 1,
+                0,
+                Some(action),
+                format!("{}{}{}", h, action, next),
+                x,
+            ));
+        } else {
+            children.push(self.dfs(k + 1, r, n, Some(action), format!("{}{}", h, action), x));
+        }
+
+        // bet or raise
+        if n < self.cap {
+            let mut x = s;
+            x[t] = s[t ^ 1] + self.bets[r as usize];
+
+            let action = if n == 0 {
+                Action::Bet(x[t])
+            } else {
+                Action::Raise(x[t])
+            };
+
+            children.push(self.dfs(k + 1, r, n + 1, Some(action), format!("{}{}", h, action), x));
+        }
+
+        self.add(k, r, a, h, s, children)
+    }
+}
+
+impl Default for Limit {
+    fn default() -> Self {
+        Self::new([1000, 2000], [2000, 2000, 4000, 4000], 4)
+    }
+}
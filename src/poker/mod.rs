@@ -1 +1,16 @@
-pub mod game;
\ No newline at end of file
+pub mod action;
+pub mod cards;
+pub mod chart;
+pub mod config;
+pub mod ev;
+pub mod game;
+pub mod limit;
+pub mod multiway;
+pub mod nolimit;
+pub mod omaha;
+pub mod query;
+pub mod range;
+pub mod rank;
+pub mod reach;
+pub mod shortdeck;
+pub mod subgame;
@@ -0,0 +1,491 @@
+use poker_evaluator::Evaluator;
+use rand::{prelude::*, seq::index};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{Error, Result},
+    interfaces::game::Game,
+};
+
+use super::{
+    action::Action,
+    cards,
+    config::PokerConfig,
+    game::{evaluator, Clusters, Recall, Variant},
+};
+
+// Seat 0 is the small blind, seat 1 the big blind and the last seat the
+// button, so the action runs upwards from seat 2 preflop and from seat 0
+// afterwards. Raises caps the bets and raises on each street.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Ring {
+    pub stacks: Vec<i32>,
+    pub blinds: [i32; 2],
+    pub ante: i32,
+    pub raises: [usize; 4],
+}
+
+impl Ring {
+    pub fn new(players: usize) -> Self {
+        Self {
+            stacks: vec![100000; players],
+            blinds: [1000, 2000],
+            ante: 0,
+            raises: [2, 1, 1, 1],
+        }
+    }
+
+    pub fn players(&self) -> usize {
+        self.stacks.len()
+    }
+}
+
+// s holds what every seat has put in, antes included, and f the folded seats
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Node {
+    pub i: usize,
+    pub t: u8,
+    pub r: u8,
+    pub a: Option<Action>,
+    pub h: String,
+
+    pub s: Vec<i32>,
+    pub f: u64,
+
+    pub c: Vec<usize>,
+}
+
+#[derive(Clone)]
+pub struct State {
+    pub cards: Vec<u64>,
+    pub board: [u64; 4],
+}
+
+impl State {
+    pub fn new(players: usize, rng: &mut impl Rng) -> Self {
+        let deck = index::sample(rng, 52, 2 * players + 5)
+            .into_iter()
+            .map(|x| 1 << x)
+            .collect::<Vec<u64>>();
+
+        let (hands, runout) = deck.split_at(2 * players);
+
+        let mut board = [0; 4];
+        board[1] = runout[0] | runout[1] | runout[2];
+        board[2] = board[1] | runout[3];
+        board[3] = board[2] | runout[4];
+
+        Self {
+            cards: hands.chunks(2).map(|x| x[0] | x[1]).collect(),
+            board,
+        }
+    }
+
+    pub fn from(cards: Vec<u64>, board: [u64; 4]) -> Self {
+        Self { cards, board }
+    }
+}
+
+// b holds the bets of the current street and pending the seats that still
+// have to act on it. Last is the size of the last full raise, and closed
+// holds the seats a short all-in left able only to call or fold.
+#[derive(Clone)]
+struct Spot {
+    r: u8,
+    t: usize,
+    s: Vec<i32>,
+    b: Vec<i32>,
+    f: u64,
+    pending: u64,
+    raises: usize,
+    last: i32,
+    closed: u64,
+}
+
+pub struct Builder {
+    pub ring: Ring,
+
+    nodes: Vec<Node>,
+}
+
+impl Builder {
+    pub fn new(ring: Ring) -> Self {
+        Self {
+            ring,
+            nodes: Vec::new(),
+        }
+    }
+
+    pub fn build(mut self) -> Vec<Node> {
+        let n = self.ring.players();
+
+        let mut b = vec![0; n];
+        b[0] = self.ring.blinds[0];
+        b[1] = self.ring.blinds[1];
+
+        let mut spot = Spot {
+            r: 0,
+            t: 2 % n,
+            s: b.iter().map(|x| x + self.ring.ante).collect(),
+            b,
+            f: 0,
+            pending: 0,
+            raises: 0,
+            last: self.ring.blinds[1],
+            closed: 0,
+        };
+        spot.pending = self.open(&spot);
+
+        self.dfs(spot, None, String::new());
+
+        self.nodes
+    }
+
+    fn add(&mut self, spot: &Spot, a: Option<Action>, h: String, c: Vec<usize>) -> usize {
+        self.nodes.push(Node {
+            i: 0,
+            t: spot.t as u8,
+            r: spot.r,
+            a,
+            h,
+            s: spot.s.clone(),
+            f: spot.f,
+            c,
+        });
+
+        self.nodes.len() - 1
+    }
+
+    // seats that have not folded and still have chips behind
+    fn open(&self, spot: &Spot) -> u64 {
+        (0..self.ring.players())
+            .filter(|&p| spot.f >> p & 1 == 0 && spot.s[p] < self.ring.stacks[p])
+            .fold(0, |acc, p| acc | 1 << p)
+    }
+
+    fn dfs(&mut self, spot: Spot, a: Option<Action>, h: String) -> usize {
+        let n = self.ring.players();
+
+        if spot.r > 3 || spot.f.count_ones() as usize == n - 1 {
+            return self.add(&spot, a, h, vec![]);
+        }
+
+        let t = spot.t;
+        let top = *spot.b.iter().max().unwrap();
+        let amount = top - spot.b[t];
+        let behind = self.ring.stacks[t] - spot.s[t];
+
+        let mut children = vec![];
+
+        // fold
+        if amount > 0 {
+            let mut x = spot.clone();
+            x.f |= 1 << t;
+
+            children.push(self.next(x, Action::Fold, &h));
+        }
+
+        // check or call, all-in for less when short
+        let mut x = spot.clone();
+        x.b[t] += amount.min(behind);
+        x.s[t] += amount.min(behind);
+
+        let action = if amount > 0 {
+            Action::Call
+        } else {
+            Action::Check
+        };
+        children.push(self.next(x, action, &h));
+
+        // bet or raise, then all-in
+        let others = self.open(&spot) & !(1 << t) != 0;
+
+        let closed = spot.closed >> t & 1 != 0;
+
+        if spot.raises < self.ring.raises[spot.r as usize] && others && !closed && behind > amount {
+            let total = if top == 0 {
+                (spot.s.iter().sum::<i32>() * 2 / 3).max(self.ring.blinds[1])
+            } else {
+                3 * top
+            };
+
+            let added = total - spot.b[t];
+            if added < behind {
+                let action = if top == 0 {
+                    Action::Bet(spot.s[t] + added)
+                } else {
+                    Action::Raise(spot.s[t] + added)
+                };
+
+                let x = self.raise(&spot, added);
+                children.push(self.next(x, action, &h));
+            }
+
+            let x = self.raise(&spot, behind);
+            children.push(self.next(x, Action::AllIn(spot.s[t] + behind), &h));
+        }
+
+        self.add(&spot, a, h, children)
+    }
+
+    fn raise(&self, spot: &Spot, added: i32) -> Spot {
+        let t = spot.t;
+
+        let top = *spot.b.iter().max().unwrap();
+
+        let mut x = spot.clone();
+        x.b[t] += added;
+        x.s[t] += added;
+        x.raises += 1;
+
+        let open = self.open(&x) & !(1 << t);
+        let raise = x.b[t] - top;
+
+        if raise >= spot.last.max(self.ring.blinds[1]) {
+            x.last = raise;
+            x.closed = 0;
+        } else {
+            // only seats yet to act may still raise
+            x.closed |= open & !spot.pending;
+        }
+        x.pending = open;
+
+        x
+    }
+
+    fn next(&mut self, mut x: Spot, action: Action, h: &str) -> usize {
+        let n = self.ring.players();
+
+        let mut h = format!("{}{}", h, action);
+
+        x.pending &= !(1 << x.t);
+
+        if x.pending == 0 {
+            let open = self.open(&x);
+
+            // with at most one seat left to bet the board simply runs out
+            if x.r == 3 || open.count_ones() <= 1 {
+                x.r = 4;
+            } else {
+                x.r += 1;
+                x.b = vec![0; n];
+                x.raises = 0;
+                x.last = 0;
+                x.closed = 0;
+                x.pending = open;
+                x.t = open.trailing_zeros() as usize;
+
+                h += "/";
+            }
+        } else {
+            x.t = (1..n)
+                .map(|i| (x.t + i) % n)
+                .find(|&p| x.pending >> p & 1 != 0)
+                .unwrap();
+        }
+
+        self.dfs(x, Some(action), h)
+    }
+}
+
+pub struct Multiway {
+    evaluator: Evaluator,
+
+    players: usize,
+
+    clusters: Clusters,
+
+    nodes: Vec<Node>,
+}
+
+impl Multiway {
+    pub fn new(path: String, ring: Ring) -> Result<Self> {
+        Self::from_config(PokerConfig::new(path), ring)
+    }
+
+    pub fn from_config(config: PokerConfig, ring: Ring) -> Result<Self> {
+        if !(3..=6).contains(&ring.players()) {
+            return Err(Error::Config(format!(
+                "multiway tables seat 3 to 6 players, got {}",
+                ring.players()
+            )));
+        }
+
+        if config.variant != Variant::Holdem || config.ranges.is_some() {
+            return Err(Error::Config(
+                "multiway games are dealt from a full hold'em deck".to_string(),
+            ));
+        }
+
+        let players = ring.players();
+
+        Self::from_nodes(config, players, Builder::new(ring).build())
+    }
+
+    pub fn from_nodes(config: PokerConfig, players: usize, mut nodes: Vec<Node>) -> Result<Self> {
+        if config.recall != Recall::Imperfect {
+            return Err(Error::Config(
+                "multiway games only support imperfect recall".to_string(),
+            ));
+        }
+
+        if !config.levels.is_empty() {
+            return Err(Error::Config(
+                "multiway games have a single abstraction level".to_string(),
+            ));
+        }
+
+        let clusters = Clusters::new(&config)?;
+
+        let mut index = 0;
+        for node in &mut nodes {
+            if !node.c.is_empty() {
+                node.i = index;
+
+                index += clusters.size(node.r);
+            }
+        }
+
+        Ok(Self {
+            evaluator: evaluator(config.evaluator)?,
+
+            players,
+
+            clusters,
+
+            nodes,
+        })
+    }
+}
+
+impl Game<Node, State> for Multiway {
+    fn done(&self, node: &Node) -> bool {
+        node.c.is_empty()
+    }
+
+    fn turn(&self, node: &Node) -> usize {
+        node.t as usize
+    }
+
+    fn next(&self, node: &Node) -> usize {
+        node.c.len()
+    }
+
+    fn init(&self, rng: &mut impl Rng) -> State {
+        State::new(self.players, rng)
+    }
+
+    fn root(&self) -> &Node {
+        self.nodes.last().unwrap()
+    }
+
+    fn size(&self) -> Vec<usize> {
+        let mut answer = Vec::new();
+
+        for node in &self.nodes {
+            if !node.c.is_empty() {
+                answer.resize(node.i + self.clusters.size(node.r), node.c.len());
+            }
+        }
+
+        answer
+    }
+
+    fn players(&self) -> usize {
+        self.players
+    }
+
+    fn eval(&self, node: &Node, state: &State) -> f64 {
+        payoff(&self.evaluator, node, state, 0)
+    }
+
+    fn utility(&self, node: &Node, state: &State, player: usize) -> f64 {
+        payoff(&self.evaluator, node, state, player)
+    }
+
+    fn play(&self, node: &Node, action: usize) -> &Node {
+        &self.nodes[node.c[action]]
+    }
+
+    fn index(&self, node: &Node, state: &State) -> usize {
+        let r = node.r;
+
+        node.i
+            + self
+                .clusters
+                .bucket(r, state.cards[node.t as usize], state.board[r as usize])
+    }
+
+    fn display(&self, node: &Node, state: &State) -> String {
+        let mut res = String::new();
+
+        for &x in &state.cards {
+            res += &cards::format(x, false);
+            res += " | ";
+        }
+
+        res += &cards::format(state.board[node.r.min(3) as usize], false);
+        res += &format!(" | {} ({})", node.h, node.t);
+
+        res
+    }
+}
+
+// Every distinct commitment of a seat still in the hand closes a side pot,
+// which goes to the best hand among the seats that put in at least as much.
+pub fn payoff(evaluator: &Evaluator, node: &Node, state: &State, player: usize) -> f64 {
+    let n = node.s.len();
+
+    let alive = (0..n)
+        .filter(|&p| node.f >> p & 1 == 0)
+        .collect::<Vec<usize>>();
+
+    let s = node.s[player] as f64;
+
+    if alive.len() == 1 {
+        let pot = node.s.iter().sum::<i32>() as f64;
+
+        return if alive[0] == player { pot - s } else { -s };
+    }
+
+    let ranks = state
+        .cards
+        .iter()
+        .map(|&x| evaluator.evaluate(x | state.board[3]))
+        .collect::<Vec<_>>();
+
+    let mut levels = alive.iter().map(|&p| node.s[p]).collect::<Vec<i32>>();
+    levels.sort_unstable();
+    levels.dedup();
+
+    let mut won = 0.0;
+    let mut prev = 0;
+
+    for level in levels {
+        let pot = node
+            .s
+            .iter()
+            .map(|&x| x.min(level) - x.min(prev))
+            .sum::<i32>();
+
+        let eligible = alive
+            .iter()
+            .copied()
+            .filter(|&p| node.s[p] >= level)
+            .collect::<Vec<usize>>();
+
+        let best = eligible.iter().map(|&p| &ranks[p]).min().unwrap();
+        let winners = eligible
+            .iter()
+            .filter(|&&p| &ranks[p] == best)
+            .collect::<Vec<_>>();
+
+        if winners.contains(&&player) {
+            won += pot as f64 / winners.len() as f64;
+        }
+
+        prev = level;
+    }
+
+    won - s
+}
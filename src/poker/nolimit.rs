@@ -0,0 +1,258 @@
+use serde::{Deserialize, Serialize};
+
+use super::{action::Action, game::Node};
+
+const OPENS: [i32; 7] = [2, 3, 5, 8, 12, 17, 23];
+const BETS: [(f64, f64); 2] = [(2.0 / 3.0, 1.0 / 3.0), (4.0 / 3.0, 2.0 / 3.0)];
+
+// Player 0 is the button. A straddle is posted by the button in place of the
+// small blind, which moves the first preflop action to the big blind. With
+// pot_limit set no total may exceed a pot-sized raise.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Table {
+    pub stacks: [i32; 2],
+    pub blinds: [i32; 2],
+    pub antes: [i32; 2],
+    pub straddle: i32,
+    pub dead: i32,
+    #[serde(default)]
+    pub pot_limit: bool,
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self {
+            stacks: [100000, 100000],
+            blinds: [1000, 2000],
+            antes: [0, 0],
+            straddle: 0,
+            dead: 0,
+            pot_limit: false,
+        }
+    }
+}
+
+impl Table {
+    pub fn posts(&self) -> [i32; 2] {
+        [
+            if self.straddle > 0 {
+                self.straddle
+            } else {
+                self.blinds[0]
+            },
+            self.blinds[1],
+        ]
+    }
+
+    // the most either player can put in beyond their ante
+    pub fn cap(&self) -> i32 {
+        (self.stacks[0] - self.antes[0]).min(self.stacks[1] - self.antes[1])
+    }
+}
+
+pub struct NoLimit {
+    pub table: Table,
+
+    cap: i32,
+    nodes: Vec<Node>,
+}
+
+impl NoLimit {
+    pub fn new(table: Table) -> Self {
+        Self {
+            cap: table.cap(),
+            table,
+            nodes: Vec::new(),
+        }
+    }
+
+    pub fn build(mut self) -> Vec<Node> {
+        let b = self.table.posts();
+
+        let last = b[0].max(b[1]);
+
+        self.dfs(0, 0, None, String::new(), b, last);
+
+        self.nodes
+    }
+
+    // a tree that starts on street r with b already matched, antes included
+    pub fn build_from(mut self, r: u8, b: [i32; 2]) -> Vec<Node> {
+        let b = [b[0] - self.table.antes[0], b[1] - self.table.antes[1]];
+
+        self.dfs(0, r, None, String::new(), b, 0);
+
+        self.nodes
+    }
+
+    fn turn(&self, k: usize, r: u8) -> usize {
+        if r == 0 {
+            (k + (self.table.straddle > 0) as usize) % 2
+        } else {
+            1 - k % 2
+        }
+    }
+
+    fn add(
+        &mut self,
+        k: usize,
+        r: u8,
+        a: Option<Action>,
+        h: String,
+        b: [i32; 2],
+        c: Vec<usize>,
+    ) -> usize {
+        self.nodes.push(Node {
+            i: 0,
+            t: self.turn(k, r) as u8,
+            r,
+            a,
+            h,
+            s: [b[0] + self.table.antes[0], b[1] + self.table.antes[1]],
+            d: self.table.dead,
+            g: 0,
+            c,
+        });
+
+        self.nodes.len() - 1
+    }
+
+    fn pot(&self, b: [i32; 2]) -> i32 {
+        b[0] + b[1] + self.table.antes[0] + self.table.antes[1] + self.table.dead
+    }
+
+    fn translate(&self, s: f64, e: f64, pot: i32) -> i32 {
+        let pot = pot as f64;
+
+        ((s - e * pot / (self.cap as f64 / (1.0 + e))) * pot) as i32
+    }
+
+    // b holds the live bets excluding antes, last the size of the last raise
+    fn dfs(
+        &mut self,
+        k: usize,
+        r: u8,
+        a: Option<Action>,
+        h: String,
+        b: [i32; 2],
+        last: i32,
+    ) -> usize {
+        if a == Some(Action::Fold) || r > 3 {
+            return self.add(k, r, a, h, b, vec![]);
+        }
+
+        if a == Some(Action::Call) && b[0] == self.cap && b[1] == self.cap {
+            return self.add(k, r, a, h, b, vec![]);
+        }
+
+        let t = self.turn(k, r);
+        let amount = b[t ^ 1] - b[t];
+
+        let mut children = vec![];
+
+        // fold
+        if amount > 0 {
+            let action = Action::Fold;
+            children.push(self.dfs(k + 1, r, Some(action), format!("{}{}", h, action), b, last));
+        }
+
+        // check or call
+        let mut x = b;
+        x[t] = b[t ^ 1];
+
+        let action = if amount > 0 {
+            Action::Call
+        } else {
+            Action::Check
+        };
+
+        if k == 0 {
+            children.push(self.dfs(k + 1, r, Some(action), format!("{}{}", h, action), x, last));
+        } else {
+            let next = if r < 3 { "/" } else { "" };
+            children.push(self.dfs(
+                0,
+                r + 1,
+                Some(action),
+                format!("{}{}{}", h, action, next),
+                x,
+                0,
+            ));
+        }
+
+        // bet or raise
+        let mut totals = vec![];
+
+        if r == 0 {
+            let base = self.table.posts()[0].max(self.table.blinds[1]);
+
+            for m in OPENS {
+                totals.push(m * base);
+            }
+        } else if amount == 0 {
+            for (s, e) in BETS {
+                totals.push(b[t] + self.translate(s, e, self.pot(b)));
+            }
+        } else {
+            totals.push(b[t] + amount * 3);
+        }
+
+        // calling and then raising the whole pot
+        let pot = b[t ^ 1] + self.pot(b) + amount;
+        let max = if self.table.pot_limit {
+            pot.min(self.cap)
+        } else {
+            self.cap
+        };
+
+        let mut totals = totals
+            .into_iter()
+            .filter(|&total| total <= self.cap / 2 && total < max)
+            .collect::<Vec<i32>>();
+
+        // beyond the stacks the all-in below takes its place
+        if self.table.pot_limit && pot < self.cap {
+            totals.push(pot);
+        }
+
+        for total in totals {
+            if total - b[t ^ 1] >= last.max(self.table.blinds[1]) {
+                let mut x = b;
+                x[t] = total;
+
+                let action = if amount == 0 && r > 0 {
+                    Action::Bet(total + self.table.antes[t])
+                } else {
+                    Action::Raise(total + self.table.antes[t])
+                };
+
+                children.push(self.dfs(
+                    k + 1,
+                    r,
+                    Some(action),
+                    format!("{}{}", h, action),
+                    x,
+                    total - b[t ^ 1],
+                ));
+            }
+        }
+
+        // all-in
+        if !matches!(a, Some(Action::AllIn(_))) && b[t] < self.cap && max == self.cap {
+            let mut x = b;
+            x[t] = self.cap;
+
+            let action = Action::AllIn(self.cap + self.table.antes[t]);
+            children.push(self.dfs(
+                k + 1,
+                r,
+                Some(action),
+                format!("{}{}", h, action),
+                x,
+                self.cap - b[t ^ 1],
+            ));
+        }
+
+        self.add(k, r, a, h, b, children)
+    }
+}
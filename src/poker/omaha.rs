@@ -0,0 +1,134 @@
+use poker_evaluator::Evaluator;
+
+use super::rank;
+
+// Four-card hands are far too many to index canonically after the flop, so
+// they are bucketed by features computed as they are dealt: preflop by the
+// ranks and suitedness of the hand, later by the best made hand using two of
+// its cards and, before the river, the flush and straight draws it holds.
+const RANKS: usize = 1820;
+const MADE: usize = 9 * 13;
+const DRAWS: usize = 3 * 4;
+
+pub const CLUSTERS: [usize; 4] = [RANKS * 3, MADE * DRAWS, MADE * DRAWS, MADE];
+
+pub fn bucket(r: u8, cards: u64, board: u64) -> usize {
+    match r {
+        0 => preflop(cards),
+        3 => made(cards, board),
+        _ => made(cards, board) * DRAWS + draws(cards, board),
+    }
+}
+
+fn choose(n: usize, k: usize) -> usize {
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+fn ranks(cards: u64) -> u32 {
+    (0..4).fold(0, |acc, s| acc | (cards >> (13 * s) & 0x1fff) as u32)
+}
+
+// the multiset of the four ranks, as its index among the 1820 there are, by
+// the number of suits holding two cards or more
+fn preflop(cards: u64) -> usize {
+    let mut ranks = (0..52)
+        .filter(|&i| cards >> i & 1 != 0)
+        .map(|i| i % 13)
+        .collect::<Vec<usize>>();
+    ranks.sort_unstable();
+
+    let index = ranks
+        .iter()
+        .enumerate()
+        .map(|(j, &r)| choose(r + j, j + 1))
+        .sum::<usize>();
+
+    let suited = (0..4)
+        .filter(|s| (cards >> (13 * s) & 0x1fff).count_ones() >= 2)
+        .count();
+
+    index * 3 + suited
+}
+
+// the category of the best hand and the rank it is made of
+fn made(cards: u64, board: u64) -> usize {
+    let boards = subsets(board, 3);
+
+    let best = subsets(cards, 2)
+        .into_iter()
+        .flat_map(|hand| boards.iter().map(move |&x| rank::holdem(hand | x)))
+        .max()
+        .unwrap();
+
+    (best >> 20) as usize * 13 + (best >> 16 & 15) as usize
+}
+
+// no flush draw, one or the nut one, by the ranks that would complete a
+// straight, counted up to three
+fn draws(cards: u64, board: u64) -> usize {
+    let mut flush = 0;
+    for s in 0..4 {
+        let hand = (cards >> (13 * s) & 0x1fff) as u32;
+        let suit = (board >> (13 * s) & 0x1fff) as u32;
+
+        if hand.count_ones() >= 2 && suit.count_ones() == 2 {
+            let highest = 31 - (!suit & 0x1fff).leading_zeros();
+
+            flush = flush.max(1 + (hand >> highest & 1) as usize);
+        }
+    }
+
+    let on = ranks(board);
+    let pairs = subsets(cards, 2)
+        .into_iter()
+        .map(ranks)
+        .filter(|x| x.count_ones() == 2)
+        .collect::<Vec<u32>>();
+
+    let mut outs = 0;
+    for window in (0..9).map(|i| 0x1f << i).chain([1 << 12 | 0xf]) {
+        for &pair in &pairs {
+            let missing = window & !pair & !on;
+
+            if pair & window == pair && missing.count_ones() == 1 {
+                outs |= missing;
+            }
+        }
+    }
+
+    flush * 4 + outs.count_ones().min(3) as usize
+}
+
+// every subset of n cards out of a mask
+pub fn subsets(cards: u64, n: u32) -> Vec<u64> {
+    if n == 0 {
+        return vec![0];
+    }
+
+    let mut res = Vec::new();
+    let mut rest = cards;
+
+    while rest != 0 {
+        let card = rest & rest.wrapping_neg();
+        rest ^= card;
+
+        for subset in subsets(rest, n - 1) {
+            res.push(card | subset);
+        }
+    }
+
+    res
+}
+
+// the best hand using exactly two hole cards and three board cards, where
+// lower is better as with poker_evaluator
+pub fn evaluate(evaluator: &Evaluator, cards: u64, board: u64) -> impl Ord {
+    let boards = subsets(board, 3);
+
+    subsets(cards, 2)
+        .into_iter()
+        .flat_map(|hand| boards.iter().map(move |&x| hand | x))
+        .map(|x| evaluator.evaluate(x))
+        .min()
+        .unwrap()
+}
@@ -0,0 +1,213 @@
+use std::fmt;
+
+use crate::{
+    error::{Error, Result},
+    interfaces::game::Game,
+    solver::{normalize, Infoset},
+};
+
+use super::{
+    action::{self, Action},
+    cards,
+    game::{Deal, Node, Poker, State},
+};
+
+pub struct Choice {
+    pub action: Action,
+    // the chips the action adds to the pot
+    pub amount: i32,
+    pub probability: f64,
+}
+
+impl Choice {
+    pub fn label(&self) -> String {
+        match self.action {
+            Action::Fold => "fold".to_string(),
+            Action::Check => "check".to_string(),
+            Action::Call => format!("call {}", self.amount),
+            Action::Bet(_) => format!("bet {}", self.amount),
+            Action::Raise(to) => format!("raise to {}", to),
+            Action::AllIn(to) => format!("all-in to {}", to),
+        }
+    }
+}
+
+// committed holds what each player has put in, antes included, and behind
+// what they have left when the game knows the stacks
+pub struct Spot {
+    pub history: String,
+    pub player: usize,
+    pub street: u8,
+
+    pub pot: i32,
+    pub committed: [i32; 2],
+    pub behind: Option<[i32; 2]>,
+
+    pub choices: Vec<Choice>,
+}
+
+impl fmt::Display for Spot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}) pot {} committed {:?}",
+            self.history, self.player, self.pot, self.committed
+        )?;
+
+        if let Some(behind) = self.behind {
+            write!(f, " behind {:?}", behind)?;
+        }
+
+        for choice in &self.choices {
+            write!(f, "\n  {:>16}: {:.3}", choice.label(), choice.probability)?;
+        }
+
+        Ok(())
+    }
+}
+
+// the choice of action a at node
+fn choice(game: &Poker, node: &Node, a: usize, probability: f64) -> Choice {
+    let t = node.t as usize;
+    let child = game.play(node, a);
+
+    Choice {
+        action: child.a.unwrap(),
+        amount: child.s[t] - node.s[t],
+        probability,
+    }
+}
+
+// the labels of the actions at node
+pub fn labels(game: &Poker, node: &Node) -> Vec<String> {
+    (0..game.next(node))
+        .map(|a| choice(game, node, a, 0.0).label())
+        .collect()
+}
+
+// a node that ends the hand has no strategy to look up
+pub fn acting(game: &Poker, node: &Node) -> Result<()> {
+    if game.done(node) {
+        return Err(Error::Notation(format!("\"{}\" ends the hand", node.h)));
+    }
+
+    Ok(())
+}
+
+// the street a board in card notation is dealt to, which has to reach the
+// street of node
+pub fn street(node: &Node, board: &str) -> Result<usize> {
+    let dealt = cards::list(board)?.len();
+
+    let street = [0, 3, 4, 5]
+        .iter()
+        .position(|&n| n == dealt)
+        .ok_or_else(|| Error::Deal(format!("a board has 0, 3, 4 or 5 cards, got {}", dealt)))?;
+
+    let needed = node.r.min(3) as usize;
+    if street < needed {
+        return Err(Error::Deal(format!(
+            "\"{}\" is on a street with {} board cards, got {}",
+            node.h,
+            [0, 3, 4, 5][needed],
+            dealt
+        )));
+    }
+
+    Ok(street)
+}
+
+// infosets solved for another tree or abstraction would be indexed wrongly
+pub fn check(game: &Poker, infosets: &[Infoset]) -> Result<()> {
+    let size = game.size();
+
+    if infosets.len() != size.len() || infosets.iter().zip(&size).any(|(x, &n)| x.s.len() != n) {
+        return Err(Error::Config(format!(
+            "the solution has {} infosets, the game {} of other sizes",
+            infosets.len(),
+            size.len()
+        )));
+    }
+
+    Ok(())
+}
+
+// the nodes a history in action notation, such as "r5000c/xb3333", passes
+// through with the action taken at each
+pub fn path<'a>(game: &'a Poker, history: &str) -> Result<Vec<(&'a Node, usize)>> {
+    let mut node = game.root();
+    let mut res = Vec::new();
+
+    for action in action::parse(history)?.into_iter().flatten() {
+        let a = (0..game.next(node))
+            .find(|&a| game.play(node, a).a == Some(action))
+            .ok_or_else(|| {
+                Error::Notation(format!(
+                    "no action '{}' after \"{}\" in the tree",
+                    action, node.h
+                ))
+            })?;
+
+        res.push((node, a));
+        node = game.play(node, a);
+    }
+
+    Ok(res)
+}
+
+// the node reached by a history
+pub fn walk<'a>(game: &'a Poker, history: &str) -> Result<&'a Node> {
+    Ok(match path(game, history)?.last() {
+        Some(&(node, a)) => game.play(node, a),
+        None => game.root(),
+    })
+}
+
+// the average strategy of the player to act after a history, holding cards
+// on board, both in card notation
+pub fn query(
+    game: &Poker,
+    infosets: &[Infoset],
+    history: &str,
+    cards: &str,
+    board: &str,
+) -> Result<Spot> {
+    check(game, infosets)?;
+
+    let node = walk(game, history)?;
+
+    acting(game, node)?;
+
+    let hand = cards::parse(cards)?;
+
+    Deal::parse([cards, ""], board)?
+        .variant(game.variant())
+        .check()?;
+
+    street(node, board)?;
+
+    let t = node.t as usize;
+    let state = State::from([hand, hand], cards::board(board)?);
+
+    let strategy = normalize(infosets[game.index(node, &state)].s.clone());
+
+    let choices = strategy
+        .into_iter()
+        .enumerate()
+        .map(|(a, probability)| choice(game, node, a, probability))
+        .collect();
+
+    Ok(Spot {
+        history: node.h.clone(),
+        player: t,
+        street: node.r,
+
+        pot: node.s[0] + node.s[1] + node.d,
+        committed: node.s,
+        behind: game
+            .stacks()
+            .map(|stacks| [stacks[0] - node.s[0], stacks[1] - node.s[1]]),
+
+        choices,
+    })
+}
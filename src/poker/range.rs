@@ -0,0 +1,123 @@
+use rand::{
+    distributions::{WeightedError, WeightedIndex},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+pub const COMBOS: usize = 1326;
+
+pub fn combo(index: usize) -> u64 {
+    let mut hi = 1;
+    while (hi + 1) * hi / 2 <= index {
+        hi += 1;
+    }
+    let lo = index - hi * (hi - 1) / 2;
+
+    1 << hi | 1 << lo
+}
+
+pub fn index(cards: u64) -> usize {
+    let hi = 63 - cards.leading_zeros() as usize;
+    let lo = cards.trailing_zeros() as usize;
+
+    hi * (hi - 1) / 2 + lo
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "Weights")]
+pub struct Range {
+    pub w: Vec<f64>,
+}
+
+// ranges read from a config are checked for a weight per combo
+#[derive(Deserialize)]
+struct Weights {
+    w: Vec<f64>,
+}
+
+impl TryFrom<Weights> for Range {
+    type Error = String;
+
+    fn try_from(weights: Weights) -> std::result::Result<Self, String> {
+        if weights.w.len() != COMBOS {
+            return Err(format!(
+                "a range has {} weights, got {}",
+                COMBOS,
+                weights.w.len()
+            ));
+        }
+
+        Ok(Self { w: weights.w })
+    }
+}
+
+impl Range {
+    pub fn new(w: Vec<f64>) -> Self {
+        assert_eq!(w.len(), COMBOS);
+
+        Self { w }
+    }
+
+    pub fn uniform() -> Self {
+        Self::new(vec![1.0; COMBOS])
+    }
+}
+
+pub struct Sampler {
+    hands: [WeightedIndex<f64>; 2],
+}
+
+impl Sampler {
+    pub fn new(ranges: &[Range; 2], dead: u64) -> Result<Self> {
+        let weights = |p: usize| {
+            WeightedIndex::new((0..COMBOS).map(|i| {
+                if combo(i) & dead != 0 {
+                    0.0
+                } else {
+                    ranges[p].w[i]
+                }
+            }))
+            .map_err(|e| {
+                Error::Config(match e {
+                    WeightedError::AllWeightsZero => {
+                        format!("the range of player {} holds no live hand", p)
+                    }
+                    e => format!("the range of player {}: {}", p, e),
+                })
+            })
+        };
+
+        let hands = [weights(0)?, weights(1)?];
+
+        let live = |range: &Range| {
+            (0..COMBOS)
+                .filter(|&i| range.w[i] > 0.0 && combo(i) & dead == 0)
+                .map(combo)
+                .collect::<Vec<u64>>()
+        };
+
+        // without a pair of disjoint hands sampling would never return
+        let (a, b) = (live(&ranges[0]), live(&ranges[1]));
+        if !a.iter().any(|x| b.iter().any(|y| x & y == 0)) {
+            return Err(Error::Config(
+                "no two hands of the ranges can be dealt together".to_string(),
+            ));
+        }
+
+        Ok(Self { hands })
+    }
+
+    // rejecting overlapping pairs keeps the joint distribution card-removal correct
+    pub fn sample(&self, rng: &mut impl Rng) -> [u64; 2] {
+        loop {
+            let a = combo(self.hands[0].sample(rng));
+            let b = combo(self.hands[1].sample(rng));
+
+            if a & b == 0 {
+                return [a, b];
+            }
+        }
+    }
+}
@@ -0,0 +1,132 @@
+// Hand ranking over up to seven cards in the State bit layout (bit = suit * 13
+// + rank), higher being better unlike poker_evaluator. The category sits in
+// the top bits above five kicker nibbles.
+const HIGH_CARD: u32 = 0;
+const PAIR: u32 = 1;
+const TWO_PAIR: u32 = 2;
+const TRIPS: u32 = 3;
+const STRAIGHT: u32 = 4;
+const QUADS: u32 = 7;
+const STRAIGHT_FLUSH: u32 = 8;
+
+// the highest rank of a straight in a 13-bit rank mask, where A-6-7-8-9
+// counts as the lowest straight in short deck and A-2-3-4-5 otherwise
+fn straight(ranks: u32, short: bool) -> Option<u32> {
+    let (lowest, wheel, high) = if short {
+        (8, 1 << 12 | 0xf << 4, 7)
+    } else {
+        (4, 1 << 12 | 0xf, 3)
+    };
+
+    for top in (lowest..13).rev() {
+        let run = 0x1f << (top - 4);
+
+        if ranks & run == run {
+            return Some(top);
+        }
+    }
+
+    if ranks & wheel == wheel {
+        return Some(high);
+    }
+
+    None
+}
+
+fn value(category: u32, kickers: &[u32]) -> u32 {
+    let mut value = category;
+    for i in 0..5 {
+        value = value << 4 | kickers.get(i).copied().unwrap_or(0);
+    }
+
+    value
+}
+
+fn top(ranks: u32, n: usize) -> Vec<u32> {
+    (0..13)
+        .rev()
+        .filter(|&r| ranks >> r & 1 != 0)
+        .take(n)
+        .collect()
+}
+
+// with hold'em rules, where full houses beat flushes, so the category in the
+// top bits follows the usual order of hands
+pub fn holdem(cards: u64) -> u32 {
+    rank(cards, false)
+}
+
+// short deck ranks flushes above full houses and plays A-6-7-8-9 straights
+pub fn rank(cards: u64, short: bool) -> u32 {
+    let (full_house, flush) = if short { (5, 6) } else { (6, 5) };
+
+    let suits = [0, 1, 2, 3].map(|s| (cards >> (13 * s) & 0x1fff) as u32);
+
+    for &ranks in &suits {
+        if let Some(high) = straight(ranks, short) {
+            return value(STRAIGHT_FLUSH, &[high]);
+        }
+    }
+
+    let counts: [usize; 13] =
+        std::array::from_fn(|r| suits.iter().filter(|&&ranks| ranks >> r & 1 != 0).count());
+
+    let with = |n: usize| {
+        (0..13)
+            .rev()
+            .filter(|&r| counts[r as usize] >= n)
+            .collect::<Vec<u32>>()
+    };
+
+    let all = suits.iter().fold(0, |acc, x| acc | x);
+
+    let quads = with(4);
+    if let Some(&q) = quads.first() {
+        return value(QUADS, &[q, top(all & !(1 << q), 1)[0]]);
+    }
+
+    let trips = with(3);
+    let pairs = with(2);
+
+    // whichever the variant ranks higher
+    let flushes = suits
+        .iter()
+        .find(|ranks| ranks.count_ones() >= 5)
+        .map(|&ranks| value(flush, &top(ranks, 5)));
+    let boats = trips.first().and_then(|&t| {
+        pairs
+            .iter()
+            .find(|&&p| p != t)
+            .map(|&p| value(full_house, &[t, p]))
+    });
+
+    if let Some(x) = flushes.max(boats) {
+        return x;
+    }
+
+    if let Some(high) = straight(all, short) {
+        return value(STRAIGHT, &[high]);
+    }
+
+    if let Some(&t) = trips.first() {
+        let mut kickers = vec![t];
+        kickers.extend(top(all & !(1 << t), 2));
+
+        return value(TRIPS, &kickers);
+    }
+
+    if pairs.len() >= 2 {
+        let (a, b) = (pairs[0], pairs[1]);
+
+        return value(TWO_PAIR, &[a, b, top(all & !(1 << a | 1 << b), 1)[0]]);
+    }
+
+    if let Some(&p) = pairs.first() {
+        let mut kickers = vec![p];
+        kickers.extend(top(all & !(1 << p), 3));
+
+        return value(PAIR, &kickers);
+    }
+
+    value(HIGH_CARD, &top(all, 5))
+}
@@ -0,0 +1,61 @@
+use crate::{
+    error::Result,
+    interfaces::game::Game,
+    solver::{normalize, Infoset},
+};
+
+use super::{
+    cards,
+    game::{Poker, State},
+    query::{check, path, street, walk},
+    range::{self, Range, COMBOS},
+};
+
+// The probability of every combo of each player being dealt and taking the
+// actions of a history under the average strategies, with board in card
+// notation. Combos that share a card with the board, or fall outside the
+// deck, never get there.
+pub fn reach(game: &Poker, infosets: &[Infoset], history: &str, board: &str) -> Result<[Range; 2]> {
+    let variant = game.variant();
+
+    variant.two_cards("ranges")?;
+
+    check(game, infosets)?;
+
+    let path = path(game, history)?;
+
+    // the board has to reach the street the history ends on
+    street(walk(game, history)?, board)?;
+
+    let board = cards::board(board)?;
+    let dead = board.iter().fold(0, |acc, x| acc | x) | cards::DECK & !variant.deck();
+
+    let mut w = [0, 1].map(|p| {
+        (0..COMBOS)
+            .map(|i| {
+                if range::combo(i) & dead != 0 {
+                    0.0
+                } else {
+                    game.ranges().map_or(1.0, |ranges| ranges[p].w[i])
+                }
+            })
+            .collect::<Vec<f64>>()
+    });
+
+    for (node, a) in path {
+        let t = node.t as usize;
+
+        for (i, x) in w[t].iter_mut().enumerate() {
+            if *x == 0.0 {
+                continue;
+            }
+
+            let cards = range::combo(i);
+            let state = State::from([cards, cards], board);
+
+            *x *= normalize(infosets[game.index(node, &state)].s.clone())[a];
+        }
+    }
+
+    Ok(w.map(Range::new))
+}
@@ -0,0 +1,10 @@
+use super::rank;
+
+// Short-deck hold'em plays with the 6 through ace of every suit, so the deck
+// keeps the State bit layout (bit = suit * 13 + rank) and skips ranks 2 to 5.
+pub const DECK: u64 = 0x1ff0 | 0x1ff0 << 13 | 0x1ff0 << 26 | 0x1ff0 << 39;
+
+// higher is better, unlike poker_evaluator
+pub fn evaluate(cards: u64) -> u32 {
+    rank::rank(cards, true)
+}
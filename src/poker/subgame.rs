@@ -0,0 +1,349 @@
+use rand::prelude::*;
+use smallvec::smallvec;
+
+use poker_evaluator::Evaluator;
+use poker_indexer::Indexer;
+
+use crate::{
+    error::{Error, Result},
+    interfaces::game::Game,
+    solver::{normalize, Infoset},
+};
+
+use super::{
+    cards,
+    config::PokerConfig,
+    game::{
+        display, evaluator, payoff, Clusters, Deal, Node, Rake, Recall, State, Variant,
+        MAX_INFOSETS,
+    },
+    nolimit::{NoLimit, Table},
+    range::{self, Range, Sampler, COMBOS},
+};
+
+pub enum Abstraction {
+    Lossless,
+    Clusters,
+}
+
+// Lossless buckets are the canonical indices of every hand and runout that
+// can still occur on the fixed board, remapped to a dense range per street.
+enum Buckets {
+    Lossless(Vec<(Indexer, Vec<u64>)>),
+    Clusters(Clusters),
+}
+
+pub struct Subgame {
+    evaluator: Evaluator,
+    variant: Variant,
+
+    rake: Rake,
+
+    buckets: Buckets,
+
+    sampler: Sampler,
+
+    street: u8,
+    board: u64,
+    deal: Deal,
+
+    nodes: Vec<Node>,
+}
+
+impl Subgame {
+    pub fn new(
+        config: PokerConfig,
+        mut nodes: Vec<Node>,
+        board: [u64; 4],
+        ranges: [Range; 2],
+        abstraction: Abstraction,
+    ) -> Result<Self> {
+        config.variant.two_cards("subgames")?;
+
+        if config.recall != Recall::Imperfect {
+            return Err(Error::Config(
+                "subgames only support imperfect recall".to_string(),
+            ));
+        }
+
+        if !config.levels.is_empty() || nodes.iter().any(|node| node.g != 0) {
+            return Err(Error::Config(
+                "subgames have a single abstraction level".to_string(),
+            ));
+        }
+
+        let Some(root) = nodes.last() else {
+            return Err(Error::Config("a subgame needs a tree".to_string()));
+        };
+
+        Clusters::check(&config)?;
+
+        let street = root.r;
+        let out = cards::DECK & !config.variant.deck();
+
+        let mut deal = Deal::new().variant(config.variant);
+        for r in 1..=street as usize {
+            deal = deal.street(board[r] & !board[r - 1]);
+        }
+        deal.check()?;
+
+        let buckets = match abstraction {
+            Abstraction::Lossless => {
+                // every flop runout gives a river node over a million hands
+                if street < 2 {
+                    return Err(Error::Config(
+                        "lossless subgames must start on the turn or river".to_string(),
+                    ));
+                }
+
+                Buckets::Lossless(Self::lossless(&config, street, board[street as usize], out))
+            }
+            Abstraction::Clusters => Buckets::Clusters(Clusters::new(&config)?),
+        };
+
+        let mut subgame = Self {
+            evaluator: evaluator(config.evaluator)?,
+            variant: config.variant,
+
+            rake: config.rake,
+
+            buckets,
+
+            sampler: Sampler::new(&ranges, board[street as usize] | out)?,
+
+            street,
+            board: board[street as usize],
+            deal,
+
+            nodes: Vec::new(),
+        };
+
+        let mut index = 0;
+        for node in &mut nodes {
+            if !node.c.is_empty() {
+                node.i = index;
+
+                index = index.saturating_add(subgame.buckets(node.r));
+
+                if index > MAX_INFOSETS {
+                    return Err(Error::Config(format!(
+                        "the subgame spans more than {} infosets by node {}",
+                        MAX_INFOSETS, node.h
+                    )));
+                }
+            }
+        }
+        subgame.nodes = nodes;
+
+        Ok(subgame)
+    }
+
+    // a fixed turn or river with a freshly built betting tree, where s
+    // holds what each player has put in before the street
+    pub fn postflop(
+        config: PokerConfig,
+        table: Table,
+        board: [u64; 4],
+        s: [i32; 2],
+        ranges: [Range; 2],
+    ) -> Result<Self> {
+        let street = (1..4).rev().find(|&r| board[r] != 0).unwrap_or(0) as u8;
+
+        let nodes = NoLimit::new(table).build_from(street, s);
+
+        Self::new(config, nodes, board, ranges, Abstraction::Lossless)
+    }
+
+    pub fn with_rake(mut self, rake: Rake) -> Self {
+        self.rake = rake;
+
+        self
+    }
+
+    // out holds the cards missing from the deck of the variant
+    fn lossless(
+        config: &PokerConfig,
+        street: u8,
+        board: u64,
+        out: u64,
+    ) -> Vec<(Indexer, Vec<u64>)> {
+        (street..4)
+            .map(|r| {
+                let indexer =
+                    Indexer::new(config.shapes[r as usize].iter().map(|&x| x as _).collect());
+
+                let mut ids = Vec::new();
+                for runout in runouts(board | out, (r - street) as usize) {
+                    let board = board | runout;
+
+                    for i in 0..COMBOS {
+                        let cards = range::combo(i);
+
+                        if cards & (board | out) == 0 {
+                            ids.push(indexer.index(smallvec![cards, board]) as u64);
+                        }
+                    }
+                }
+
+                ids.sort_unstable();
+                ids.dedup();
+
+                (indexer, ids)
+            })
+            .collect()
+    }
+
+    fn bucket(&self, r: u8, cards: u64, board: u64) -> usize {
+        match &self.buckets {
+            Buckets::Lossless(streets) => {
+                let (indexer, ids) = &streets[(r - self.street) as usize];
+
+                ids.binary_search(&(indexer.index(smallvec![cards, board]) as u64))
+                    .unwrap()
+            }
+            Buckets::Clusters(clusters) => clusters.bucket(r, cards, board),
+        }
+    }
+
+    fn buckets(&self, r: u8) -> usize {
+        match &self.buckets {
+            Buckets::Lossless(streets) => streets[(r - self.street) as usize].1.len(),
+            Buckets::Clusters(clusters) => clusters.size(r),
+        }
+    }
+
+    pub fn strategy(&self, infosets: &[Infoset], node: &Node, state: &State) -> Vec<f64> {
+        normalize(infosets[self.index(node, state)].s.clone())
+    }
+
+    // the average strategy of every hand the acting player can hold at a
+    // node on the subgame street
+    pub fn strategies(&self, infosets: &[Infoset], node: &Node) -> Vec<(u64, Vec<f64>)> {
+        assert_eq!(node.r, self.street);
+
+        let mut board = [0; 4];
+        board[self.street as usize] = self.board;
+
+        (0..COMBOS)
+            .map(range::combo)
+            .filter(|&cards| cards & (self.board | cards::DECK & !self.variant.deck()) == 0)
+            .map(|cards| {
+                let state = State::from([cards, cards], board);
+
+                (cards, self.strategy(infosets, node, &state))
+            })
+            .collect()
+    }
+}
+
+// every set of n cards that avoids the dead ones
+fn runouts(dead: u64, n: usize) -> Vec<u64> {
+    if n == 0 {
+        return vec![0];
+    }
+
+    let mut res = Vec::new();
+    for runout in runouts(dead, n - 1) {
+        let low = 64 - runout.leading_zeros();
+
+        for card in low..52 {
+            if dead & 1 << card == 0 {
+                res.push(runout | 1 << card);
+            }
+        }
+    }
+
+    res
+}
+
+impl Game<Node, State> for Subgame {
+    fn done(&self, node: &Node) -> bool {
+        node.c.is_empty()
+    }
+
+    fn turn(&self, node: &Node) -> usize {
+        node.t as usize
+    }
+
+    fn next(&self, node: &Node) -> usize {
+        node.c.len()
+    }
+
+    fn init(&self, rng: &mut impl Rng) -> State {
+        let cards = self.sampler.sample(rng);
+
+        let mut state = self
+            .deal
+            .clone()
+            .cards(0, cards[0])
+            .cards(1, cards[1])
+            .build(rng)
+            .unwrap();
+
+        state.showdown = Some(
+            self.variant
+                .compare(&self.evaluator, state.cards, state.board[3]),
+        );
+
+        // streets before the subgame are never indexed
+        let mut buckets = [[0; 4]; 2];
+        for (player, x) in buckets.iter_mut().enumerate() {
+            for r in self.street..4 {
+                x[r as usize] = self.bucket(r, state.cards[player], state.board[r as usize]);
+            }
+        }
+        state.buckets = Some(vec![buckets]);
+
+        state
+    }
+
+    fn root(&self) -> &Node {
+        self.nodes.last().unwrap()
+    }
+
+    fn size(&self) -> Vec<usize> {
+        let mut answer = Vec::new();
+
+        for node in &self.nodes {
+            if !node.c.is_empty() {
+                answer.resize(node.i + self.buckets(node.r), node.c.len());
+            }
+        }
+
+        answer
+    }
+
+    fn eval(&self, node: &Node, state: &State) -> f64 {
+        payoff(&self.evaluator, self.variant, &self.rake, node, state, 0)
+    }
+
+    fn utility(&self, node: &Node, state: &State, player: usize) -> f64 {
+        payoff(
+            &self.evaluator,
+            self.variant,
+            &self.rake,
+            node,
+            state,
+            player,
+        )
+    }
+
+    fn play(&self, node: &Node, action: usize) -> &Node {
+        &self.nodes[node.c[action]]
+    }
+
+    fn index(&self, node: &Node, state: &State) -> usize {
+        let r = node.r;
+        let t = node.t as usize;
+
+        node.i
+            + match &state.buckets {
+                Some(buckets) => buckets[0][t][r as usize],
+                None => self.bucket(r, state.cards[t], state.board[r as usize]),
+            }
+    }
+
+    fn display(&self, node: &Node, state: &State) -> String {
+        display(node, state)
+    }
+}
@@ -1,11 +1,14 @@
-use std::sync::Mutex;
+use std::{fs::File, io::BufWriter, sync::Mutex};
 
 use rand::prelude::*;
 use rayon::prelude::*;
 
 use serde::{Deserialize, Serialize};
 
-use crate::interfaces::game::Game;
+use crate::{
+    error::{self, Error, Result},
+    interfaces::game::Game,
+};
 
 pub fn normalize(input: Vec<f64>) -> Vec<f64> {
     let sum: f64 = input.iter().sum();
@@ -72,7 +75,7 @@ fn mccfr<Node, State>(
     // println!("{}", game.display(node, state));
 
     if game.done(node) {
-        return game.eval(node, &state);
+        return game.utility(node, &state, player);
     }
 
     if game.turn(node) == player {
@@ -97,10 +100,8 @@ fn mccfr<Node, State>(
             .zip(infoset.get_strategy())
             .fold(0.0, |acc, (x, p)| acc + x * p);
 
-        let who = if game.turn(node) == 0 { 1.0 } else { -1.0 };
-
         for i in 0..n {
-            infoset.update_regret(i, (u[i] - s) * who);
+            infoset.update_regret(i, u[i] - s);
         }
 
         s
@@ -126,38 +127,48 @@ pub fn solve<Node: Sync + Send, State: Sync + Send + Clone>(
         .map(|size| Mutex::new(Infoset::new(size)))
         .collect::<Vec<_>>();
 
-    let scores: [f64; 2] = (0..n)
+    let players = game.players();
+
+    let scores = (0..n)
         .into_par_iter()
         .map(|i| {
             let mut rng = SmallRng::seed_from_u64(seed + i);
 
-            let mut scores = [0.0; 2];
-            scores[0] += mccfr(
-                0,
-                &game.init(&mut rng),
-                game.root(),
-                game,
-                &infosets,
-                &mut rng,
-            );
-            scores[1] -= mccfr(
-                1,
-                &game.init(&mut rng),
-                game.root(),
-                game,
-                &infosets,
-                &mut rng,
-            );
-
-            scores
+            (0..players)
+                .map(|player| {
+                    mccfr(
+                        player,
+                        &game.init(&mut rng),
+                        game.root(),
+                        game,
+                        &infosets,
+                        &mut rng,
+                    )
+                })
+                .collect::<Vec<f64>>()
         })
-        .reduce(|| [0.0; 2], |acc, ele| [acc[0] + ele[0], acc[1] + ele[1]]);
+        .reduce(
+            || vec![0.0; players],
+            |acc, ele| acc.iter().zip(ele).map(|(a, b)| a + b).collect(),
+        );
 
-    println!("player 0: {}", scores[0] / n as f64);
-    println!("player 1: {}", scores[1] / n as f64);
+    for (player, score) in scores.iter().enumerate() {
+        println!("player {}: {}", player, score / n as f64);
+    }
 
     infosets
         .into_iter()
         .map(|infoset| infoset.into_inner().unwrap())
         .collect()
 }
+
+pub fn save(path: &str, infosets: &[Infoset]) -> Result<()> {
+    let file = File::create(path).map_err(|e| Error::Io(path.to_string(), e))?;
+
+    bincode::serialize_into(BufWriter::new(file), infosets)
+        .map_err(|e| Error::Bincode(path.to_string(), e))
+}
+
+pub fn load(path: &str) -> Result<Vec<Infoset>> {
+    bincode::deserialize_from(error::open(path)?).map_err(|e| Error::Bincode(path.to_string(), e))
+}
@@ -0,0 +1,201 @@
+use rand::prelude::*;
+use smallvec::smallvec;
+
+use poker_solver::{
+    abstraction::{
+        builder::{write, Builder, Features},
+        kmeans::{emd, kmeans, Metric},
+        report::River,
+    },
+    interfaces::game::Game,
+    poker::{
+        cards,
+        config::PokerConfig,
+        game::{self, Variant},
+    },
+    solver::{solve, Infoset},
+};
+
+#[test]
+fn test_kmeans() {
+    let mut rng = SmallRng::seed_from_u64(42);
+
+    let centers = [[0.0, 0.0], [10.0, 0.0], [0.0, 10.0]];
+
+    let mut data = Vec::new();
+    for i in 0..300 {
+        for x in centers[i % 3] {
+            data.push(x + rng.gen_range(-1.0..1.0));
+        }
+    }
+
+    let labels = kmeans(&data, 2, 3, 100, 42, Metric::L2);
+
+    assert_eq!(labels.len(), 300);
+    for i in 0..300 {
+        assert_eq!(labels[i], labels[i % 3]);
+    }
+
+    let mut distinct = labels[..3].to_vec();
+    distinct.sort();
+    distinct.dedup();
+
+    assert_eq!(distinct.len(), 3);
+}
+
+#[test]
+fn test_emd() {
+    assert_eq!(emd(&[1.0, 0.0, 0.0], &[0.0, 1.0, 0.0]), 1.0);
+    assert_eq!(emd(&[1.0, 0.0, 0.0], &[0.0, 0.0, 1.0]), 2.0);
+    assert_eq!(emd(&[0.5, 0.0, 0.5], &[0.0, 1.0, 0.0]), 1.0);
+
+    // a draw and a made hand of the same average strength stay apart
+    let draw = [0.5, 0.0, 0.0, 0.0, 0.5];
+    let made = [0.0, 0.0, 1.0, 0.0, 0.0];
+
+    let mut data = Vec::new();
+    for _ in 0..10 {
+        data.extend(draw);
+        data.extend(made);
+    }
+
+    let labels = kmeans(&data, 5, 2, 100, 42, Metric::Emd);
+
+    assert_ne!(labels[0], labels[1]);
+    assert!(labels.chunks(2).all(|x| x == &labels[..2]));
+}
+
+#[test]
+fn test_write() {
+    let path = std::env::temp_dir().join("cluster-test.bin");
+    let path = path.to_str().unwrap();
+
+    let table = (0..1000).map(|x| x % 7).collect::<Vec<u16>>();
+    write(path, &table).unwrap();
+
+    assert_eq!(game::table(path.to_string()).unwrap(), table);
+}
+
+#[test]
+fn test_features() {
+    let builder = Builder::new(&PokerConfig::new("data/abstraction/".to_string())).unwrap();
+
+    let mut rng = SmallRng::seed_from_u64(42);
+
+    let nuts = builder.features(
+        cards::parse("AsAh").unwrap(),
+        cards::parse("AcAd2s7h9c").unwrap(),
+        &mut rng,
+    );
+    let air = builder.features(
+        cards::parse("2c3d").unwrap(),
+        cards::parse("AsKsQhJh9c").unwrap(),
+        &mut rng,
+    );
+
+    assert!(nuts[0] > 0.95 && air[0] < 0.1);
+
+    let flop = builder.features(
+        cards::parse("AsAh").unwrap(),
+        cards::parse("Ac7d2s").unwrap(),
+        &mut rng,
+    );
+
+    assert_eq!(flop.len(), builder.bins);
+    assert!((flop.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+    assert!(flop[builder.bins - 1] > 0.5);
+}
+
+#[test]
+fn test_potential() {
+    let mut builder = Builder::new(&PokerConfig::new("data/abstraction/".to_string())).unwrap();
+
+    // a flop of one card and a turn of two keep the tables small
+    builder.shapes = vec![vec![2], vec![2, 1], vec![2, 2], vec![2, 2]];
+    builder.clusters = vec![169, 2, 16, 16];
+    builder.features = Features::Potential;
+
+    // turn hands that pair a board card are the strongest
+    let pair = |cards: u64, board: u64| {
+        (0..13).any(|r| {
+            let rank = (0..4).fold(0, |x, s| x | cards::card(r, s));
+
+            cards & rank != 0 && board & rank != 0
+        })
+    };
+
+    let indexer = builder.indexer(2);
+    let next = (0..indexer.count[1])
+        .map(|i| {
+            let x = indexer.unindex(i, 1);
+
+            if pair(x[0], x[1]) {
+                15
+            } else {
+                0
+            }
+        })
+        .collect::<Vec<u16>>();
+
+    let table = builder.build(1, Some(&next));
+
+    let indexer = builder.indexer(1);
+    assert_eq!(table.len(), indexer.count[1] as usize);
+    assert!(table.iter().all(|&x| x < 2));
+
+    let bucket = |cards: &str, board: &str| {
+        let cards = cards::parse(cards).unwrap();
+        let board = cards::parse(board).unwrap();
+
+        table[indexer.index(smallvec![cards, board]) as usize]
+    };
+
+    assert_eq!(bucket("AsKd", "Ah"), 1);
+    assert_eq!(bucket("AsKd", "2h"), 0);
+    assert_eq!(bucket("2c2d", "7h"), 0);
+}
+
+#[test]
+fn test_river_exploitability() {
+    let board = cards::parse("AsKd7c4h2s").unwrap();
+
+    let game = River::lossless(
+        game::evaluator("data/evaluator".to_string()).unwrap(),
+        Variant::Holdem,
+        board,
+    );
+
+    let uniform = game
+        .size()
+        .into_iter()
+        .map(Infoset::new)
+        .collect::<Vec<_>>();
+    let solved = solve(1000000, 42, &game);
+
+    let a = game.exploitability(&solved);
+    let b = game.exploitability(&uniform);
+
+    assert!(a > -1e-9 && a < b);
+}
+
+#[test]
+fn test_river_shortdeck() {
+    let board = cards::parse("AsKd7c9h6s").unwrap();
+
+    let game = River::lossless(
+        game::evaluator("data/evaluator".to_string()).unwrap(),
+        Variant::ShortDeck,
+        board,
+    );
+
+    let mut rng = SmallRng::seed_from_u64(42);
+    for _ in 0..1000 {
+        let state = game.init(&mut rng);
+
+        assert_eq!(
+            (state.cards[0] | state.cards[1]) & !Variant::ShortDeck.deck(),
+            0
+        );
+        assert_eq!((state.cards[0] | state.cards[1]) & board, 0);
+    }
+}
@@ -0,0 +1,88 @@
+use rand::prelude::*;
+
+use poker_solver::poker::{cards, game::Deal};
+
+#[test]
+fn test_parse() {
+    assert_eq!(cards::parse("AsKd").unwrap(), 1 << 12 | 1 << 24);
+    assert_eq!(cards::parse("2s").unwrap(), 1);
+    assert_eq!(cards::parse("2h").unwrap(), 1 << 39);
+    assert_eq!(
+        cards::parse("Th 9h 2c").unwrap(),
+        1 << 47 | 1 << 46 | 1 << 26
+    );
+    assert_eq!(cards::parse("").unwrap(), 0);
+
+    assert!(cards::parse("AsK").is_err());
+    assert!(cards::parse("Ax").is_err());
+    assert!(cards::parse("1s").is_err());
+    assert!(cards::parse("AsAs").is_err());
+}
+
+#[test]
+fn test_board() {
+    let board = cards::board("Th9h2cJd5s").unwrap();
+
+    assert_eq!(board[0], 0);
+    assert_eq!(board[1], cards::parse("Th9h2c").unwrap());
+    assert_eq!(board[2], cards::parse("Th9h2cJd").unwrap());
+    assert_eq!(board[3], cards::parse("Th9h2cJd5s").unwrap());
+
+    assert_eq!(cards::board("Th9h2c").unwrap()[2], 0);
+    assert!(cards::board("Th9h").is_err());
+}
+
+#[test]
+fn test_format() {
+    for input in ["AsKd", "Th9h2c", "Ah2s", "QcJc7d3d"] {
+        let x = cards::parse(input).unwrap();
+
+        assert_eq!(cards::parse(&cards::format(x, true)).unwrap(), x);
+    }
+
+    assert_eq!(cards::format(cards::parse("AsKd").unwrap(), true), "AsKd");
+    assert_eq!(cards::format(cards::parse("AsKd").unwrap(), false), "A♠ K♦");
+    assert_eq!(cards::format(0, true), "");
+}
+
+#[test]
+fn test_deal() {
+    let mut rng = SmallRng::seed_from_u64(42);
+
+    for _ in 0..100 {
+        let state = Deal::parse(["AsKd", "7c7h"], "Th9h2c")
+            .unwrap()
+            .build(&mut rng)
+            .unwrap();
+
+        assert_eq!(
+            state.cards,
+            [cards::parse("AsKd").unwrap(), cards::parse("7c7h").unwrap()]
+        );
+        assert_eq!(state.board[0], 0);
+        assert_eq!(state.board[1], cards::parse("Th9h2c").unwrap());
+        assert_eq!(state.board[2] & state.board[1], state.board[1]);
+        assert_eq!(state.board[3].count_ones(), 5);
+        assert_eq!((state.cards[0] | state.cards[1]) & state.board[3], 0);
+    }
+
+    let state = Deal::new()
+        .cards(0, cards::parse("AsAd").unwrap())
+        .build(&mut rng)
+        .unwrap();
+
+    assert_eq!(state.cards[1].count_ones(), 2);
+    assert_eq!(state.cards[0] & state.cards[1], 0);
+
+    assert!(Deal::parse(["AsKd", "AsQd"], "").unwrap().check().is_err());
+    assert!(Deal::parse(["AsKd", "Qd"], "").unwrap().check().is_err());
+    assert!(Deal::parse(["AsKd", "QdJd"], "Th9h2cAs")
+        .unwrap()
+        .check()
+        .is_err());
+    assert!(Deal::parse(["AsKd", "QdJd"], "Th9h").is_err());
+    assert!(Deal::new()
+        .street(cards::parse("Th").unwrap())
+        .check()
+        .is_err());
+}
@@ -0,0 +1,89 @@
+use poker_solver::{
+    interfaces::game::Game,
+    poker::{
+        cards,
+        chart::{chart, class, CLASSES},
+        game::Poker,
+        query::walk,
+    },
+    solver::Infoset,
+};
+
+#[test]
+fn test_class() {
+    let hands = [
+        ("AhKd2c7d9h", "high card"),
+        ("AhAd2c7d9h", "pair"),
+        ("Ah2d2c7d7h", "two pair"),
+        ("7s2d2c7d7h", "full house"),
+        ("Ah2d3c4d5h", "straight"),
+        ("Ah2h3h4h5h", "straight flush"),
+        ("AhKh3h4h9hKdKs", "flush"),
+        ("KcKh3h4h9hKdKs", "quads"),
+        ("Kc9c3h4h9hKd9s", "full house"),
+        ("ThJdQcKdAs9h", "straight"),
+        ("2h2d2c7d9h", "trips"),
+    ];
+
+    for (hand, name) in hands {
+        assert_eq!(
+            CLASSES[class(cards::parse(hand).unwrap())],
+            name,
+            "{}",
+            hand
+        );
+    }
+}
+
+#[test]
+fn test_chart() {
+    let game = Poker::limit("data/abstraction/".to_string()).unwrap();
+
+    let infosets = game
+        .size()
+        .into_iter()
+        .map(Infoset::new)
+        .collect::<Vec<Infoset>>();
+
+    let root = game.root();
+    let preflop = chart(&game, &infosets, root, "").unwrap();
+
+    assert!(preflop.grid);
+    assert_eq!(preflop.hands.len(), 169);
+    assert_eq!(preflop.hands[0], "AA");
+    assert_eq!(preflop.hands[1], "AKs");
+    assert_eq!(preflop.hands[13], "AKo");
+    assert_eq!(preflop.hands[168], "22");
+    assert_eq!(preflop.combos[0], 6);
+    assert_eq!(preflop.combos[1], 4);
+    assert_eq!(preflop.combos[13], 12);
+    assert_eq!(preflop.combos.iter().sum::<usize>(), 1326);
+
+    let n = game.next(root) as f64;
+    for row in &preflop.freq {
+        for &x in row {
+            assert!((x - 1.0 / n).abs() < 1e-9);
+        }
+    }
+
+    let csv = preflop.csv();
+    assert_eq!(csv.lines().count(), 170);
+    assert!(csv.lines().nth(1).unwrap().starts_with("AA,6,"));
+
+    assert!(preflop.html().contains("<td"));
+    assert_eq!(
+        preflop.terminal().lines().count(),
+        preflop.actions.len() * 14
+    );
+
+    let node = walk(&game, "cx/").unwrap();
+
+    assert!(chart(&game, &infosets, node, "").is_err());
+
+    let postflop = chart(&game, &infosets, node, "2c7d9h").unwrap();
+
+    assert!(!postflop.grid);
+    assert_eq!(postflop.hands.len(), CLASSES.len());
+    assert_eq!(postflop.combos.iter().sum::<usize>(), 49 * 48 / 2);
+    assert_eq!(postflop.combos[8], 0);
+}
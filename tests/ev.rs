@@ -0,0 +1,130 @@
+use poker_solver::{
+    interfaces::game::Game,
+    poker::{
+        cards,
+        ev::{values, Rollout},
+        game::Poker,
+        query::walk,
+        range::{self, Range, COMBOS},
+    },
+    solver::Infoset,
+};
+
+#[test]
+fn test_values() {
+    let game = Poker::limit("data/abstraction/".to_string()).unwrap();
+
+    let infosets = game
+        .size()
+        .into_iter()
+        .map(Infoset::new)
+        .collect::<Vec<Infoset>>();
+
+    let river = walk(&game, "cx/xx/xx/").unwrap();
+    let node = game.play(river, 1);
+
+    let board = "2c7d9hTsKh";
+    let blocked = cards::parse(board).unwrap();
+
+    let exact = values(&game, &infosets, &node.h, board, Rollout::Exact, 42).unwrap();
+    let sampled = values(&game, &infosets, &node.h, board, Rollout::Sampled(200), 42).unwrap();
+
+    assert_eq!(exact.player, node.t as usize);
+    assert_eq!(exact.actions[0], "fold");
+
+    let n = game.next(node) as f64;
+    let mut diff = 0.0;
+    let mut hands = 0;
+
+    for i in 0..COMBOS {
+        if range::combo(i) & blocked != 0 {
+            assert!(exact.ev[i].is_none());
+            assert!(exact.loss(i, 0).is_none());
+            continue;
+        }
+
+        let ev = exact.ev[i].as_ref().unwrap();
+
+        // folding gives up what was put in, whatever the hand
+        assert_eq!(ev[0], -node.s[exact.player] as f64);
+        assert_eq!(sampled.ev[i].as_ref().unwrap()[0], ev[0]);
+
+        for &p in exact.strategy[i].as_ref().unwrap() {
+            assert!((p - 1.0 / n).abs() < 1e-9);
+        }
+
+        let losses = (0..ev.len())
+            .map(|a| exact.loss(i, a).unwrap())
+            .collect::<Vec<f64>>();
+        assert!(losses.iter().all(|&x| x >= 0.0));
+        assert!(losses.contains(&0.0));
+        assert!(exact.regret(i).unwrap() >= 0.0);
+
+        diff += sampled.ev[i].as_ref().unwrap()[1] - ev[1];
+        hands += 1;
+    }
+
+    // sampling errors of the call values cancel out over the hands
+    let pot = (node.s[0] + node.s[1]) as f64;
+    assert!((diff / hands as f64).abs() < 0.02 * pot);
+
+    assert!(values(&game, &infosets, &node.h, "2c7d9h", Rollout::Exact, 42).is_err());
+    assert!(values(&game, &infosets, &node.h, board, Rollout::Sampled(0), 42).is_err());
+}
+
+#[test]
+fn test_values_turn() {
+    // the button only holds aces, which keeps exact rollouts over the river
+    // cheap
+    let button = (0..COMBOS)
+        .map(|i| {
+            let x = range::combo(i);
+            let aces = (0..4).filter(|s| x >> (s * 13 + 12) & 1 != 0).count();
+
+            if aces == 2 {
+                1.0
+            } else {
+                0.0
+            }
+        })
+        .collect();
+
+    let game = Poker::limit("data/abstraction/".to_string())
+        .unwrap()
+        .with_ranges([Range::new(button), Range::uniform()])
+        .unwrap();
+
+    let infosets = game
+        .size()
+        .into_iter()
+        .map(Infoset::new)
+        .collect::<Vec<Infoset>>();
+
+    let node = walk(&game, "cx/xx/").unwrap();
+    let board = "2c7d9hTs";
+
+    let exact = values(&game, &infosets, &node.h, board, Rollout::Exact, 42).unwrap();
+    let sampled = values(&game, &infosets, &node.h, board, Rollout::Sampled(200), 42).unwrap();
+
+    assert_eq!(exact.player, 1);
+    assert_eq!(exact.actions[0], "check");
+
+    let mut diff = [0.0; 2];
+    let mut hands = 0;
+
+    for i in 0..COMBOS {
+        let (Some(exact), Some(sampled)) = (&exact.ev[i], &sampled.ev[i]) else {
+            continue;
+        };
+
+        for a in 0..2 {
+            diff[a] += sampled[a] - exact[a];
+        }
+        hands += 1;
+    }
+
+    // the river is dealt by both, exhaustively or at random
+    let pot = (node.s[0] + node.s[1]) as f64;
+    assert!(hands > 0);
+    assert!(diff.iter().all(|x| (x / hands as f64).abs() < 0.02 * pot));
+}
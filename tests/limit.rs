@@ -1,243 +1,177 @@
-use std::{fs::File, io::BufReader};
-
-use smallvec::smallvec;
-
 use colored::*;
 
 use rand::prelude::*;
-use serde::{Deserialize, Serialize};
 
-use poker_abstraction::tables::{get, load};
-use poker_evaluator::Evaluator;
+use poker_abstraction::tables::get;
 use poker_indexer::Indexer;
 use poker_solver::{
+    error::Error,
     interfaces::game::Game,
+    poker::{
+        action::{self, Action},
+        config::PokerConfig,
+        game::{tree, Node, Poker, State},
+        limit::Limit,
+    },
     solver::{normalize, solve},
 };
 
-const CLUSTERS: [usize; 4] = [169, 2197, 2197, 2197];
+fn bets(nodes: &Vec<Node>, node: &Node, r: u8, n: usize) -> usize {
+    let mut res = n;
 
-#[derive(Serialize, Deserialize)]
-pub struct Node {
-    pub i: usize,
-    pub t: u8,
-    pub r: u8,
-    pub a: char,
-    pub h: String,
+    for &c in &node.c {
+        let child = &nodes[c];
 
-    pub s: [i32; 2],
-
-    x: Vec<usize>,
-}
+        if child.r == r && matches!(child.a, Some(Action::Bet(_) | Action::Raise(_))) {
+            res = res.max(bets(nodes, child, r, n + 1));
+        } else if child.r == r {
+            res = res.max(bets(nodes, child, r, n));
+        } else {
+            res = res.max(bets(nodes, child, child.r, 0));
+        }
+    }
 
-#[derive(Clone)]
-pub struct State {
-    used: u64,
-    cards: [u64; 2],
-    board: [u64; 4],
+    res
 }
 
-impl State {
-    pub fn new(rng: &mut impl Rng) -> Self {
-        let mut state = Self {
-            used: 0,
-            cards: [rng.gen(), rng.gen()],
-            board: [0; 4],
-        };
-
-        state.cards[0] = state.gen(rng) | state.gen(rng);
-        state.cards[1] = state.gen(rng) | state.gen(rng);
-
-        state.board[1] = state.gen(rng) | state.gen(rng) | state.gen(rng);
-        state.board[2] = state.gen(rng) | state.board[1];
-        state.board[3] = state.gen(rng) | state.board[2];
+#[test]
+fn test_limit_tree() {
+    let nodes = Limit::default().build();
 
-        state
-    }
+    let root = nodes.last().unwrap();
 
-    fn gen(&mut self, rng: &mut impl Rng) -> u64 {
-        loop {
-            let x = 1 << rng.gen_range(0..52);
+    assert_eq!(root.s, [1000, 2000]);
+    assert_eq!(root.t, 0);
 
-            if self.used & x != 0 {
-                continue;
-            }
-            self.used |= x;
+    assert_eq!(bets(&nodes, root, 0, 1), 4);
 
-            return x;
+    for node in &nodes {
+        if node.c.is_empty() && node.a != Some(Action::Fold) {
+            assert_eq!(node.r, 4);
+            assert_eq!(node.s[0], node.s[1]);
         }
-    }
 
-    pub fn from(cards: [u64; 2], board: [u64; 4]) -> Self {
-        Self {
-            used: 0,
-            cards,
-            board,
+        if node.a == Some(Action::Fold) {
+            assert_ne!(node.s[0], node.s[1]);
         }
     }
-}
 
-pub struct Mock {
-    evaluator: Evaluator,
+    let mut node = &nodes[root.c[1]];
+    assert_eq!(node.s, [2000, 2000]);
+    assert_eq!(node.t, 1);
 
-    cluster_1: Vec<u16>,
-    cluster_2: Vec<u16>,
-    cluster_3: Vec<u16>,
+    node = &nodes[node.c[0]];
+    assert_eq!(node.s, [2000, 2000]);
+    assert_eq!((node.r, node.t), (1, 1));
 
-    indexer_0: Indexer,
-    indexer_1: Indexer,
-    indexer_2: Indexer,
-    indexer_3: Indexer,
+    node = &nodes[node.c[1]];
+    node = &nodes[node.c[2]];
 
-    nodes: Vec<Node>,
+    assert_eq!(node.s, [6000, 4000]);
+    assert_eq!(node.a, Some(Action::Raise(6000)));
+    assert_eq!(node.h, "cx/b4000r6000");
 }
 
-impl Mock {
-    pub fn new() -> Self {
-        let path = "data/abstraction/".to_string();
-
-        Self {
-            evaluator: Evaluator::new("data/evaluator".to_string()),
-
-            cluster_1: load(&(path.clone() + "cluster_1.bin")),
-            cluster_2: load(&(path.clone() + "cluster_2.bin")),
-            cluster_3: load(&(path.clone() + "cluster_3.bin")),
+#[test]
+fn test_action_notation() {
+    let streets = action::parse("r6000c/xb8000c/b12000r16000a100000").unwrap();
+
+    assert_eq!(
+        streets,
+        vec![
+            vec![Action::Raise(6000), Action::Call],
+            vec![Action::Check, Action::Bet(8000), Action::Call],
+            vec![
+                Action::Bet(12000),
+                Action::Raise(16000),
+                Action::AllIn(100000)
+            ],
+        ]
+    );
 
-            indexer_0: Indexer::new(vec![2, 0]),
-            indexer_1: Indexer::new(vec![2, 3]),
-            indexer_2: Indexer::new(vec![2, 4]),
-            indexer_3: Indexer::new(vec![2, 5]),
+    assert_eq!(
+        action::format(&streets),
+        "r6000c/xb8000c/b12000r16000a100000"
+    );
 
-            nodes: serde_json::from_reader(BufReader::new(
-                File::open("tests/data/limit-tree.json").unwrap(),
-            ))
-            .unwrap(),
-        }
-    }
+    assert!(action::parse("cb/x").is_err());
+    assert!(action::parse("cz").is_err());
+    assert!(action::parse("ca").is_err());
 }
 
-impl Game<Node, State> for Mock {
-    fn done(&self, node: &Node) -> bool {
-        node.x.is_empty()
-    }
-
-    fn turn(&self, node: &Node) -> usize {
-        node.t as usize
-    }
-
-    fn next(&self, node: &Node) -> usize {
-        node.x.len()
-    }
-
-    fn init(&self, rng: &mut impl Rng) -> State {
-        State::new(rng)
-    }
-
-    fn root(&self) -> &Node {
-        &self.nodes[0]
-    }
-
-    fn size(&self) -> Vec<usize> {
-        let n = self
-            .nodes
-            .iter()
-            .map(|node| {
-                if node.x.len() > 0 {
-                    CLUSTERS[node.r as usize]
-                } else {
-                    0
-                }
+#[test]
+fn test_legacy_tree() {
+    let nodes = Limit::default().build();
+
+    let legacy = nodes
+        .iter()
+        .map(|node| {
+            let a = match node.a {
+                None => '.',
+                Some(Action::Fold) => 'f',
+                Some(Action::Check | Action::Call) => 'c',
+                Some(Action::Bet(_)) => 'b',
+                Some(Action::Raise(_)) => 'x',
+                Some(Action::AllIn(_)) => 'a',
+            };
+
+            serde_json::json!({
+                "i": node.i,
+                "t": node.t,
+                "r": node.r,
+                "a": a,
+                "h": "",
+                "s": node.s,
+                "c": node.c,
             })
-            .sum();
-
-        let mut answer = vec![0; n];
-
-        for node in &self.nodes {
-            if node.x.len() > 0 {
-                for i in 0..CLUSTERS[node.r as usize] {
-                    answer[node.i + i] = node.x.len();
-                }
-            }
-        }
-
-        answer
-    }
-
-    fn eval(&self, node: &Node, state: &State) -> f64 {
-        let me = (node.t ^ 0) as usize;
-        let op = (node.t ^ 1) as usize;
-
-        if node.a == 'f' {
-            (node.s[op] * if me == 0 { 1 } else { -1 }) as f64
-        } else {
-            let me_score = self.evaluator.evaluate(state.cards[0] | state.board[3]);
-            let op_score = self.evaluator.evaluate(state.cards[1] | state.board[3]);
-
-            if me_score < op_score {
-                return node.s[op] as f64;
-            }
-
-            if me_score > op_score {
-                return node.s[me] as f64 * -1.0;
-            }
+        })
+        .collect::<Vec<_>>();
 
-            return 0.0;
-        }
-    }
+    let path = std::env::temp_dir().join("legacy-tree.json");
+    std::fs::write(&path, serde_json::to_string(&legacy).unwrap()).unwrap();
 
-    fn play(&self, node: &Node, action: usize) -> &Node {
-        &self.nodes[node.x[action]]
-    }
+    let migrated = tree(path.to_str().unwrap()).unwrap();
 
-    fn index(&self, node: &Node, state: &State) -> usize {
-        let input = smallvec![state.cards[node.t as usize], state.board[node.r as usize]];
-        node.i
-            + match node.r {
-                0 => self.indexer_0.index(input) as usize,
-                1 => self.cluster_1[self.indexer_1.index(input) as usize] as usize,
-                2 => self.cluster_2[self.indexer_2.index(input) as usize] as usize,
-                3 => self.cluster_3[self.indexer_3.index(input) as usize] as usize,
-                _ => panic!("not possible"),
-            }
+    assert_eq!(migrated.len(), nodes.len());
+    for (a, b) in migrated.iter().zip(&nodes) {
+        assert_eq!(a.a, b.a);
+        assert_eq!(a.h, b.h);
     }
+}
 
-    fn display(&self, node: &Node, state: &State) -> String {
-        let ranks = [
-            "2", "3", "4", "5", "6", "7", "8", "9", "T", "J", "Q", "K", "A",
-        ];
-
-        let suits = ["♠", "♦", "♣", "♥"];
-
-        let mut res = String::new();
+#[test]
+fn test_missing_files() {
+    let error = Poker::limit("data/missing/".to_string()).err().unwrap();
 
-        let deal = [state.cards[node.t as usize], state.board[node.r as usize]];
-        for mut cards in deal {
-            while cards != 0 {
-                let card = 63 - cards.leading_zeros();
-                let rank = card % 13;
-                let suit = card / 13;
+    assert!(matches!(error, Error::Missing(path) if path == "data/missing/cluster_1.bin"));
 
-                res += &format!("{}{} ", ranks[rank as usize], suits[suit as usize]);
+    let path = std::env::temp_dir().join("limit-corrupt.bin");
+    std::fs::write(&path, [3, 0, 0, 0, 0, 0, 0, 0, 1, 0]).unwrap();
 
-                cards &= !(1 << card);
-            }
-            res += "| "
-        }
+    let mut config = PokerConfig::new("data/abstraction/".to_string());
+    config.tables[1] = Some(path.to_str().unwrap().to_string());
 
-        res += &format!("{} ({})", node.h, node.t);
+    let error = Poker::from_nodes(config, Limit::default().build())
+        .err()
+        .unwrap();
 
-        res
-    }
+    assert!(matches!(error, Error::Corrupt(x) if x == path.to_str().unwrap()));
 }
 
 #[test]
 fn test_solve() {
     let infosets = get(
         &"tests/data/limit-sol.bin".to_string(),
-        Box::new(|| solve(10000000, 42, &Mock::new())),
+        Box::new(|| {
+            solve(
+                10000000,
+                42,
+                &Poker::limit("data/abstraction/".to_string()).unwrap(),
+            )
+        }),
     );
 
-    let game = Mock::new();
+    let game = Poker::limit("data/abstraction/".to_string()).unwrap();
 
     let indexer = Indexer::new(vec![2, 3, 1, 1]);
 
@@ -280,10 +214,16 @@ fn test_solve() {
 fn test_solve_pre() {
     let infosets = get(
         &"tests/data/limit-sol.bin".to_string(),
-        Box::new(|| solve(10000000, 42, &Mock::new())),
+        Box::new(|| {
+            solve(
+                10000000,
+                42,
+                &Poker::limit("data/abstraction/".to_string()).unwrap(),
+            )
+        }),
     );
 
-    let game = Mock::new();
+    let game = Poker::limit("data/abstraction/".to_string()).unwrap();
 
     let mut matrix = vec![vec![vec![0.0; 13]; 13]; 2];
 
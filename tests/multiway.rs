@@ -0,0 +1,187 @@
+use poker_evaluator::Evaluator;
+use poker_solver::{
+    error::Error,
+    interfaces::game::Game,
+    poker::{
+        action::Action,
+        cards,
+        multiway::{payoff, Builder, Multiway, Node, Ring, State},
+    },
+    solver::solve,
+};
+
+fn node(s: Vec<i32>, f: u64) -> Node {
+    Node {
+        i: 0,
+        t: 0,
+        r: 4,
+        a: Some(Action::Call),
+        h: String::new(),
+        s,
+        f,
+        c: vec![],
+    }
+}
+
+#[test]
+fn test_multiway_tree() {
+    for players in 3..=6 {
+        let ring = Ring {
+            raises: [1, 0, 0, 0],
+            ..Ring::new(players)
+        };
+
+        let nodes = Builder::new(ring).build();
+
+        let root = nodes.last().unwrap();
+
+        assert_eq!(root.t, 2 % players as u8);
+        assert_eq!(root.s[..2], [1000, 2000]);
+        assert!(root.s[2..].iter().all(|&x| x == 0));
+
+        // everyone folds to the big blind
+        let mut node = root;
+        while !node.c.is_empty() {
+            node = &nodes[node.c[0]];
+        }
+
+        assert_eq!(node.f.count_ones() as usize, players - 1);
+        assert_eq!(node.f >> 1 & 1, 0);
+
+        for node in &nodes {
+            assert!(node.s.iter().all(|&x| x <= 100000));
+
+            if node.c.is_empty() && node.f.count_ones() as usize + 1 < players {
+                assert_eq!(node.r, 4);
+            }
+        }
+    }
+
+    // limping round: the blinds complete, then the small blind opens the flop
+    let nodes = Builder::new(Ring::new(3)).build();
+
+    let mut node = nodes.last().unwrap();
+    for action in [0, 1, 0] {
+        node = &nodes[node.c[action]];
+    }
+
+    assert_eq!(node.h, "fcx/");
+    assert_eq!((node.r, node.t), (1, 0));
+}
+
+#[test]
+fn test_short_allin() {
+    let ring = Ring {
+        stacks: vec![100000, 100000, 7000],
+        raises: [3, 1, 1, 1],
+        ..Ring::new(3)
+    };
+
+    let nodes = Builder::new(ring).build();
+
+    let play = |node: &Node, action: Action| {
+        node.c
+            .iter()
+            .map(|&c| &nodes[c])
+            .find(|child| child.a == Some(action))
+            .unwrap()
+    };
+
+    // the button shoves 1000 over a raise to 6000
+    let mut node = nodes.last().unwrap();
+    for action in [
+        Action::Call,
+        Action::Call,
+        Action::Raise(6000),
+        Action::AllIn(7000),
+    ] {
+        node = play(node, action);
+    }
+
+    // the small blind has yet to act on the raise and may raise again
+    assert_eq!(node.t, 0);
+    assert!(node
+        .c
+        .iter()
+        .any(|&c| matches!(nodes[c].a, Some(Action::Raise(_)))));
+
+    // the big blind only gets to call or fold
+    let node = play(node, Action::Call);
+
+    assert_eq!(node.t, 1);
+    assert_eq!(
+        node.c
+            .iter()
+            .map(|&c| nodes[c].a.unwrap())
+            .collect::<Vec<_>>(),
+        [Action::Fold, Action::Call]
+    );
+}
+
+#[test]
+fn test_ring() {
+    let error = Multiway::new("data/abstraction/".to_string(), Ring::new(2))
+        .err()
+        .unwrap();
+
+    assert!(matches!(error, Error::Config(_)));
+
+    let ring = Ring {
+        stacks: vec![10000, 100000, 100000],
+        ante: 200,
+        ..Ring::new(3)
+    };
+
+    let nodes = Builder::new(ring).build();
+
+    assert_eq!(nodes.last().unwrap().s, [1200, 2200, 200]);
+
+    for node in &nodes {
+        assert!(node.s[0] <= 10000);
+    }
+}
+
+#[test]
+fn test_side_pots() {
+    let evaluator = Evaluator::new("data/evaluator".to_string());
+
+    let board = [0, 0, 0, cards::parse("2c7d9hTs4h").unwrap()];
+    let state = State::from(
+        vec![
+            cards::parse("AsAd").unwrap(),
+            cards::parse("KsKd").unwrap(),
+            cards::parse("QsQd").unwrap(),
+            cards::parse("3s4s").unwrap(),
+        ],
+        board,
+    );
+
+    // the short aces win the main pot, the kings the side pot and the
+    // folded seat loses its blind
+    let node = node(vec![1000, 5000, 5000, 500], 1 << 3);
+
+    assert_eq!(payoff(&evaluator, &node, &state, 0), 2500.0);
+    assert_eq!(payoff(&evaluator, &node, &state, 1), 3000.0);
+    assert_eq!(payoff(&evaluator, &node, &state, 2), -5000.0);
+    assert_eq!(payoff(&evaluator, &node, &state, 3), -500.0);
+
+    let node = self::node(vec![1000, 2000, 2000, 2000], 0b1101);
+
+    assert_eq!(payoff(&evaluator, &node, &state, 1), 5000.0);
+}
+
+#[test]
+fn test_multiway_solve() {
+    let ring = Ring {
+        raises: [1, 1, 1, 1],
+        ..Ring::new(3)
+    };
+
+    let game = Multiway::new("data/abstraction/".to_string(), ring).unwrap();
+
+    assert_eq!(game.players(), 3);
+
+    let infosets = solve(10000, 42, &game);
+
+    assert_eq!(infosets.len(), game.size().len());
+}
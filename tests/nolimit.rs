@@ -0,0 +1,264 @@
+use poker_evaluator::Evaluator;
+use poker_solver::poker::{
+    action::Action,
+    game::{payoff, Node, Rake, State, Variant},
+    nolimit::{NoLimit, Table},
+};
+
+fn showdown<'a>(nodes: &'a [Node], mut node: &'a Node) -> &'a Node {
+    while !node.c.is_empty() {
+        node = node
+            .c
+            .iter()
+            .map(|&c| &nodes[c])
+            .find(|child| matches!(child.a, Some(Action::Check | Action::Call)))
+            .unwrap();
+    }
+
+    node
+}
+
+#[test]
+fn test_nolimit_tree() {
+    let nodes = NoLimit::new(Table::default()).build();
+
+    let root = nodes.last().unwrap();
+
+    assert_eq!(root.s, [1000, 2000]);
+    assert_eq!(root.t, 0);
+
+    for node in &nodes {
+        assert!(node.s[0] <= 100000 && node.s[1] <= 100000);
+
+        if node.c.is_empty() && node.a != Some(Action::Fold) {
+            assert_eq!(node.s[0], node.s[1]);
+        }
+    }
+
+    let node = showdown(&nodes, root);
+
+    assert_eq!(node.s, [2000, 2000]);
+    assert_eq!(node.h, "cx/xx/xx/xx");
+}
+
+#[test]
+fn test_table() {
+    let table = Table {
+        stacks: [50000, 120000],
+        blinds: [1000, 2000],
+        antes: [200, 200],
+        straddle: 4000,
+        dead: 500,
+        ..Table::default()
+    };
+
+    let nodes = NoLimit::new(table).build();
+
+    let root = nodes.last().unwrap();
+
+    assert_eq!(root.s, [4200, 2200]);
+    assert_eq!(root.t, 1);
+
+    for node in &nodes {
+        assert!(node.s[0] <= 50000 && node.s[1] <= 50000);
+        assert_eq!(node.d, 500);
+    }
+
+    let allin = root
+        .c
+        .iter()
+        .map(|&c| &nodes[c])
+        .find(|child| child.a == Some(Action::AllIn(50000)))
+        .unwrap();
+
+    assert_eq!(allin.s, [4200, 50000]);
+}
+
+#[test]
+fn test_payoffs() {
+    let evaluator = Evaluator::new("data/evaluator".to_string());
+    let rake = Rake::default();
+
+    let table = Table {
+        antes: [200, 200],
+        straddle: 4000,
+        dead: 500,
+        ..Table::default()
+    };
+
+    let nodes = NoLimit::new(table).build();
+
+    let root = nodes.last().unwrap();
+
+    // the big blind folds to the straddle
+    let fold = &nodes[root.c[0]];
+    let state = State::from([0, 0], [0; 4]);
+
+    assert_eq!(fold.a, Some(Action::Fold));
+    assert_eq!(
+        payoff(&evaluator, Variant::Holdem, &rake, fold, &state, 0),
+        (2200 + 500) as f64
+    );
+
+    let node = showdown(&nodes, root);
+    assert_eq!(node.s, [4200, 4200]);
+
+    let board = 1 << 11 | 1 << 23 | 1 << 33 | 1 << 41 | 1 << 14;
+    let state = State::from([1 << 12 | 1 << 25, 1 << 31 | 1 << 39], [0, 0, 0, board]);
+
+    assert_eq!(
+        payoff(&evaluator, Variant::Holdem, &rake, node, &state, 0),
+        (4200 + 500) as f64
+    );
+
+    let state = State::from([1 << 39 | 1 << 25, 1 << 12 | 1 << 31], [0, 0, 0, board]);
+
+    assert_eq!(
+        payoff(&evaluator, Variant::Holdem, &rake, node, &state, 0),
+        -4200.0
+    );
+
+    // both players play a royal flush on the board and split the dead money
+    let board = 1 << 8 | 1 << 9 | 1 << 10 | 1 << 11 | 1 << 12;
+    let state = State::from([1 << 13 | 1 << 14, 1 << 26 | 1 << 27], [0, 0, 0, board]);
+
+    assert_eq!(
+        payoff(&evaluator, Variant::Holdem, &rake, node, &state, 0),
+        250.0
+    );
+}
+
+#[test]
+fn test_rake() {
+    let evaluator = Evaluator::new("data/evaluator".to_string());
+    let rake = Rake {
+        percent: 5.0,
+        cap: Some(150),
+        no_flop_no_drop: true,
+    };
+
+    let nodes = NoLimit::new(Table::default()).build();
+
+    let root = nodes.last().unwrap();
+
+    // no flop, no drop
+    let fold = &nodes[root.c[0]];
+    let state = State::from([0, 0], [0; 4]);
+
+    assert_eq!(
+        payoff(&evaluator, Variant::Holdem, &rake, fold, &state, 0),
+        -1000.0
+    );
+    assert_eq!(
+        payoff(&evaluator, Variant::Holdem, &rake, fold, &state, 1),
+        1000.0
+    );
+
+    let node = showdown(&nodes, root);
+
+    let board = 1 << 11 | 1 << 23 | 1 << 33 | 1 << 41 | 1 << 14;
+    let state = State::from([1 << 12 | 1 << 25, 1 << 31 | 1 << 39], [0, 0, 0, board]);
+
+    assert_eq!(
+        payoff(&evaluator, Variant::Holdem, &rake, node, &state, 0),
+        1850.0
+    );
+    assert_eq!(
+        payoff(&evaluator, Variant::Holdem, &rake, node, &state, 1),
+        -2000.0
+    );
+
+    let board = 1 << 8 | 1 << 9 | 1 << 10 | 1 << 11 | 1 << 12;
+    let state = State::from([1 << 13 | 1 << 14, 1 << 26 | 1 << 27], [0, 0, 0, board]);
+
+    assert_eq!(
+        payoff(&evaluator, Variant::Holdem, &rake, node, &state, 0),
+        -75.0
+    );
+    assert_eq!(
+        payoff(&evaluator, Variant::Holdem, &rake, node, &state, 1),
+        -75.0
+    );
+}
+
+#[test]
+fn test_build_from() {
+    let nodes = NoLimit::new(Table::default()).build_from(3, [10000, 10000]);
+
+    let root = nodes.last().unwrap();
+
+    assert_eq!((root.r, root.t), (3, 1));
+    assert_eq!(root.s, [10000, 10000]);
+    assert_eq!(root.a, None);
+
+    for node in &nodes {
+        assert!(node.r >= 3);
+
+        if node.c.is_empty() && node.a != Some(Action::Fold) {
+            assert_eq!(node.r, 4);
+        }
+    }
+
+    let node = showdown(&nodes, root);
+
+    assert_eq!(node.h, "xx");
+
+    let table = Table {
+        antes: [500, 500],
+        ..Table::default()
+    };
+
+    let nodes = NoLimit::new(table).build_from(3, [10000, 10000]);
+
+    assert_eq!(nodes.last().unwrap().s, [10000, 10000]);
+}
+
+#[test]
+fn test_pot_limit() {
+    let table = Table {
+        pot_limit: true,
+        ..Table::default()
+    };
+
+    let nodes = NoLimit::new(table).build();
+
+    let root = nodes.last().unwrap();
+
+    let actions = root
+        .c
+        .iter()
+        .map(|&c| nodes[c].a.unwrap())
+        .collect::<Vec<Action>>();
+
+    assert_eq!(
+        actions,
+        [
+            Action::Fold,
+            Action::Call,
+            Action::Raise(4000),
+            Action::Raise(6000)
+        ]
+    );
+
+    for node in &nodes {
+        let pot = node.s[0] + node.s[1];
+
+        for &c in &node.c {
+            let child = &nodes[c];
+            let t = node.t as usize;
+
+            if !matches!(child.a, Some(Action::AllIn(_))) {
+                assert!(child.s[t] <= node.s[t ^ 1] + pot + node.s[t ^ 1] - node.s[t]);
+            }
+
+            // a pot-sized raise reaching the stacks is left to the all-in
+            if matches!(child.a, Some(Action::Bet(_) | Action::Raise(_))) {
+                assert!(child.s[t] < 100000);
+            }
+        }
+    }
+
+    assert!(nodes
+        .iter()
+        .any(|node| matches!(node.a, Some(Action::AllIn(_)))));
+}
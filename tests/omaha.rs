@@ -0,0 +1,198 @@
+use rand::prelude::*;
+
+use poker_evaluator::Evaluator;
+use poker_solver::{
+    error::Error,
+    interfaces::game::Game,
+    poker::{
+        cards,
+        config::PokerConfig,
+        game::{Clusters, Deal, Poker, Variant},
+        nolimit::Table,
+        omaha,
+    },
+    solver::{normalize, solve},
+};
+
+#[test]
+fn test_subsets() {
+    let cards = cards::parse("AsKsQsJs").unwrap();
+
+    let pairs = omaha::subsets(cards, 2);
+
+    assert_eq!(pairs.len(), 6);
+    assert!(pairs.iter().all(|&x| x.count_ones() == 2 && x & cards == x));
+
+    assert_eq!(
+        omaha::subsets(cards::parse("Th9h2cJd5s").unwrap(), 3).len(),
+        10
+    );
+}
+
+#[test]
+fn test_showdown() {
+    let evaluator = Evaluator::new("data/evaluator".to_string());
+
+    let board = cards::parse("2h5h8hJhKc").unwrap();
+
+    // a single heart in hand makes no flush
+    let aces = cards::parse("AhAsKsQs").unwrap();
+    let flush = cards::parse("3h4h7d9d").unwrap();
+
+    assert_eq!(
+        Variant::Omaha.compare(&evaluator, [aces, flush], board),
+        std::cmp::Ordering::Greater
+    );
+
+    // the fourth board heart does not play either
+    let board = cards::parse("2h5h8hJh9c").unwrap();
+    let pair = cards::parse("AhKsQs9s").unwrap();
+    let high = cards::parse("AsKdQd3s").unwrap();
+
+    assert_eq!(
+        Variant::Omaha.compare(&evaluator, [pair, high], board),
+        std::cmp::Ordering::Less
+    );
+}
+
+#[test]
+fn test_deal() {
+    let mut rng = SmallRng::seed_from_u64(42);
+
+    for _ in 0..100 {
+        let state = Deal::new().variant(Variant::Omaha).build(&mut rng).unwrap();
+
+        assert_eq!(state.cards[0].count_ones(), 4);
+        assert_eq!(state.cards[1].count_ones(), 4);
+        assert_eq!(state.cards[0] & state.cards[1], 0);
+        assert_eq!(state.board[3].count_ones(), 5);
+    }
+
+    assert!(Deal::parse(["AsKd", ""], "")
+        .unwrap()
+        .variant(Variant::Omaha)
+        .check()
+        .is_err());
+}
+
+#[test]
+fn test_config() {
+    let config = PokerConfig::omaha("data/abstraction/".to_string());
+
+    // no table is read, so the missing directory does not matter
+    assert!(Clusters::new(&config).is_ok());
+
+    let mut bad = config.clone();
+    bad.tables[1] = Some("data/abstraction/cluster_1.bin".to_string());
+    assert!(matches!(Clusters::new(&bad), Err(Error::Config(_))));
+
+    let mut bad = config;
+    bad.clusters[1] = 2197;
+    assert!(matches!(Clusters::new(&bad), Err(Error::Config(_))));
+}
+
+#[test]
+fn test_buckets() {
+    let bucket = |r: u8, cards: &str, board: &str| {
+        omaha::bucket(
+            r,
+            cards::parse(cards).unwrap(),
+            cards::parse(board).unwrap(),
+        )
+    };
+
+    // every multiset of four ranks, dealt from distinct suits, keys apart
+    let mut seen = vec![false; omaha::CLUSTERS[0] / 3];
+    for a in 0..13 {
+        for b in a..13 {
+            for c in b..13 {
+                for d in c..13 {
+                    let cards = [a, b, c, d]
+                        .iter()
+                        .enumerate()
+                        .fold(0, |acc, (s, &r)| acc | cards::card(r, s));
+
+                    let key = omaha::bucket(0, cards, 0);
+                    assert!(key < omaha::CLUSTERS[0]);
+                    assert!(!seen[key / 3]);
+                    seen[key / 3] = true;
+                }
+            }
+        }
+    }
+    assert!(seen.iter().all(|&x| x));
+
+    // double suited, then rainbow
+    assert_eq!(bucket(0, "AsAhKsKh", ""), bucket(0, "AsAdKcKh", "") + 2);
+
+    let draws = omaha::CLUSTERS[1] / omaha::CLUSTERS[3];
+
+    // a royal flush with two hearts in hand, none with a single one
+    assert_eq!(bucket(3, "AhKh2c3d", "QhJhTh4s5s") / 13, 8);
+    assert_eq!(bucket(2, "Ah2c3d4s", "KhQhJhTh") / draws / 13, 0);
+
+    // the nut flush draw, a lower one and two straight outs
+    assert_eq!(bucket(1, "AhKh2c3d", "Qh7h2s") % draws, 8);
+    assert_eq!(bucket(1, "KhJh2c3d", "Qh7h2s") % draws, 4);
+    assert_eq!(bucket(1, "JsTd2c3h", "9c8h2s") % draws, 2);
+
+    let mut rng = SmallRng::seed_from_u64(42);
+    for _ in 0..1000 {
+        let state = Deal::new().variant(Variant::Omaha).build(&mut rng).unwrap();
+
+        for r in 0..4 {
+            assert!(
+                omaha::bucket(r, state.cards[0], state.board[r as usize])
+                    < omaha::CLUSTERS[r as usize]
+            );
+        }
+    }
+}
+
+#[test]
+fn test_solve() {
+    let table = Table {
+        stacks: [10000, 10000],
+        ..Table::default()
+    };
+
+    let game = Poker::omaha("data/abstraction/".to_string(), table).unwrap();
+
+    let infosets = solve(10000, 42, &game);
+
+    let size = game.size();
+    assert_eq!(infosets.len(), size.len());
+
+    let mut rng = SmallRng::seed_from_u64(42);
+
+    let mut seen = 0;
+    for _ in 0..100 {
+        let state = game.init(&mut rng);
+
+        let mut node = game.root();
+        while !game.done(node) {
+            let index = game.index(node, &state);
+            assert_eq!(size[index], game.next(node));
+
+            if infosets[index].s.iter().sum::<f64>() > 0.0 {
+                seen += 1;
+
+                let strategy = normalize(infosets[index].s.clone());
+                assert!((strategy.iter().sum::<f64>() - 1.0).abs() < 1e-6);
+            }
+
+            node = game.play(node, rng.gen_range(0..game.next(node)));
+        }
+    }
+
+    assert!(seen > 0);
+
+    // an explored bucket shows hands that fall into it
+    let node = game.root();
+    let state = game.init(&mut rng);
+    let bucket = game.explore(game.index(node, &state), 3).unwrap();
+
+    for &(cards, board) in &bucket.hands {
+        assert_eq!(omaha::bucket(0, cards, board), bucket.buckets[0].unwrap());
+    }
+}
@@ -1,73 +1,367 @@
-use colored::*;
+use rand::prelude::*;
 
 use poker_abstraction::tables::get;
+use poker_indexer::Indexer;
 use poker_solver::{
+    error::Error,
     interfaces::game::Game,
-    poker::game::{Poker, State},
-    solver::{normalize, solve},
+    poker::{
+        action::Action,
+        cards,
+        chart::chart,
+        config::{Level, PokerConfig},
+        game::{Node, Poker, Recall, State, CLUSTERS},
+        limit::Limit,
+        range::{self, Range, COMBOS},
+    },
+    solver::solve,
 };
 
 #[test]
-fn test_poker_solve() {
-    let infosets = get(
-        &"tests/data/poker-sol.bin".to_string(),
-        Box::new(|| solve(50000000, 42, &Poker::new("data/abstraction/".to_string()))),
+fn test_poker_config() {
+    let mut config = PokerConfig::new("data/abstraction/".to_string());
+    config.clusters = vec![169, 500, 500, 500];
+    config.rake.percent = 5.0;
+
+    let path = std::env::temp_dir().join("poker-config.json");
+    std::fs::write(&path, serde_json::to_string(&config).unwrap()).unwrap();
+
+    let config = PokerConfig::load(path.to_str().unwrap()).unwrap();
+
+    assert_eq!(config.tree, "data/abstraction/poker-tree.json");
+    assert_eq!(
+        config.tables[3],
+        Some("data/abstraction/cluster_3.bin".to_string())
     );
+    assert_eq!(config.clusters, vec![169, 500, 500, 500]);
+    assert_eq!(config.shapes[2], vec![2, 4]);
+    assert_eq!(config.rake.percent, 5.0);
+}
 
-    let game = Poker::new("data/abstraction/".to_string());
+#[test]
+fn test_poker_config_errors() {
+    let config = || PokerConfig::new("data/abstraction/".to_string());
 
-    let node = game.root();
-    // let node = game.play(node, 1);
-    // let node = game.play(node, 1);
+    let error = |config: PokerConfig, nodes: Vec<Node>| {
+        matches!(Poker::from_nodes(config, nodes), Err(Error::Config(_)))
+    };
 
-    println!("{}: {}", node.h, node.i);
+    let mut bad = config();
+    bad.clusters.pop();
+    assert!(error(bad, Limit::default().build()));
+
+    let mut bad = config();
+    bad.shapes[2] = vec![4, 4];
+    assert!(error(bad, Limit::default().build()));
+
+    let mut bad = config();
+    bad.levels = vec![Level {
+        pot: None,
+        tables: vec![None; 3],
+        clusters: CLUSTERS.to_vec(),
+    }];
+    assert!(error(bad, Limit::default().build()));
+
+    let mut nodes = Limit::default().build();
+    nodes[0].g = 1;
+    assert!(error(config(), nodes));
+
+    // a lossless street needs as many clusters as canonical hands
+    let mut bad = config();
+    bad.tables[1] = None;
+    assert!(error(bad, Limit::default().build()));
+
+    let mut bad = config();
+    bad.levels = vec![Level {
+        pot: Some(6000),
+        tables: vec![
+            None,
+            Some("data/abstraction/cluster_1.bin".to_string()),
+            None,
+            None,
+        ],
+        clusters: CLUSTERS.to_vec(),
+    }];
+    assert!(error(bad, Limit::default().build()));
+}
+
+#[test]
+fn test_poker_ranges() {
+    let aces = cards::parse("AsAd").unwrap();
+    let kings = cards::parse("KcKh").unwrap();
+
+    let mut opening = vec![0.0; COMBOS];
+    opening[range::index(aces)] = 3.0;
+    opening[range::index(kings)] = 1.0;
+
+    let game = Poker::new("data/abstraction/".to_string())
+        .unwrap()
+        .with_ranges([Range::new(opening), Range::uniform()])
+        .unwrap();
+
+    let mut rng = SmallRng::seed_from_u64(42);
+
+    let mut count = 0;
+    for _ in 0..10000 {
+        let state = game.init(&mut rng);
+
+        assert!(state.cards[0] == aces || state.cards[0] == kings);
+        assert_eq!(state.cards[0] & state.cards[1], 0);
+        assert_eq!((state.cards[0] | state.cards[1]) & state.board[3], 0);
 
-    let mut matrix = vec![vec![vec![0.0; 13]; 13]; node.c.len()];
-    for a in 0..13 {
-        for b in 0..13 {
-            let a_card = 1 << a;
-            let b_card = 1 << b << if a < b { 0 } else { 13 };
+        if state.cards[0] == aces {
+            count += 1;
+        }
+    }
+
+    assert!((7000..8000).contains(&count));
+}
+
+#[test]
+fn test_poker_cache() {
+    let game = Poker::new("data/abstraction/".to_string()).unwrap();
+
+    let mut rng = SmallRng::seed_from_u64(42);
 
-            let i = game.index(
-                node,
-                &State::from([a_card | b_card, a_card | b_card], [0; 4]),
+    for _ in 0..1000 {
+        let state = game.init(&mut rng);
+        let fresh = State::from(state.cards, state.board);
+
+        let mut node = game.root();
+        while !game.done(node) {
+            assert_eq!(game.index(node, &state), game.index(node, &fresh));
+
+            node = game.play(node, rng.gen_range(0..game.next(node)));
+        }
+
+        for player in 0..2 {
+            assert_eq!(
+                game.utility(node, &state, player),
+                game.utility(node, &fresh, player)
             );
+        }
+    }
+}
 
-            let s = normalize(infosets[i].s.clone());
+// a preflop call and a flop of checks or one bet, keyed on both streets
+fn flop() -> Vec<Node> {
+    let node = |t: u8, r: u8, a: Option<Action>, h: &str, s: [i32; 2], c: Vec<usize>| Node {
+        i: 0,
+        t,
+        r,
+        a,
+        h: h.to_string(),
+        s,
+        d: 0,
+        g: 0,
+        c,
+    };
 
-            for i in 0..matrix.len() {
-                matrix[i][a][b] = s[i];
-            }
+    vec![
+        node(1, 0, Some(Action::Fold), "f", [1000, 2000], vec![]),
+        node(0, 4, Some(Action::Check), "c/xx", [2000, 2000], vec![]),
+        node(0, 1, Some(Action::Fold), "c/xb4000f", [4000, 2000], vec![]),
+        node(0, 4, Some(Action::Call), "c/xb4000c", [4000, 4000], vec![]),
+        node(
+            1,
+            1,
+            Some(Action::Bet(4000)),
+            "c/xb4000",
+            [4000, 2000],
+            vec![2, 3],
+        ),
+        node(0, 1, Some(Action::Check), "c/x", [2000, 2000], vec![1, 4]),
+        node(1, 1, Some(Action::Fold), "c/b4000f", [2000, 4000], vec![]),
+        node(0, 4, Some(Action::Call), "c/b4000c", [4000, 4000], vec![]),
+        node(
+            0,
+            1,
+            Some(Action::Bet(4000)),
+            "c/b4000",
+            [2000, 4000],
+            vec![6, 7],
+        ),
+        node(1, 1, Some(Action::Call), "c/", [2000, 2000], vec![5, 8]),
+        node(0, 0, None, "", [1000, 2000], vec![0, 9]),
+    ]
+}
+
+// the canonical hands of a street, which a lossless street has as clusters
+fn lossless(r: usize) -> usize {
+    Indexer::new(vec![2, r + 2]).count[1] as usize
+}
+
+// a flop table putting the canonical hands into k clusters in turn
+fn flop_table(name: &str, k: usize) -> String {
+    let count = Indexer::new(vec![2, 3]).count[1];
+
+    let mut data = count.to_le_bytes().to_vec();
+    for i in 0..count {
+        data.extend(((i % k as u64) as u16).to_le_bytes());
+    }
+
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, data).unwrap();
+
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn test_poker_recall() {
+    // every street of the full tree is far too many infosets
+    let mut config = PokerConfig::new("data/abstraction/".to_string());
+    config.recall = Recall::Perfect;
+
+    assert!(matches!(
+        Poker::from_nodes(config, Limit::default().build()),
+        Err(Error::Config(_))
+    ));
+
+    let mut config = PokerConfig::new("data/abstraction/".to_string());
+    config.recall = Recall::Perfect;
+    config.tables = vec![None, Some(flop_table("poker-recall.bin", 4)), None, None];
+    config.clusters = vec![169, 4, lossless(2), lossless(3)];
+
+    let game = Poker::from_nodes(config, flop()).unwrap();
+
+    let size = game.size();
+    assert_eq!(size.len(), 169 + 4 * 169 * 4);
+
+    let mut rng = SmallRng::seed_from_u64(42);
+
+    for _ in 0..1000 {
+        let state = game.init(&mut rng);
+        let fresh = State::from(state.cards, state.board);
+
+        let buckets = state.buckets.clone().unwrap()[0];
+
+        let mut node = game.root();
+        while !game.done(node) {
+            let t = node.t as usize;
+            let key = match node.r {
+                0 => buckets[t][0],
+                _ => buckets[t][0] * 4 + buckets[t][1],
+            };
+
+            assert_eq!(game.index(node, &state), node.i + key);
+            assert_eq!(game.index(node, &fresh), node.i + key);
+            assert_eq!(size[node.i + key], game.next(node));
+
+            node = game.play(node, rng.gen_range(0..game.next(node)));
         }
     }
 
-    let display = |matrix: &Vec<Vec<f64>>| {
-        let mut res = String::new();
+    let infosets = solve(1000000, 42, &game);
+
+    assert_eq!(infosets.len(), size.len());
+}
+
+#[test]
+fn test_poker_levels() {
+    // four flop clusters until the pot reaches 6000, then two
+    let mut config = PokerConfig::new("data/abstraction/".to_string());
+    config.tables = vec![None, Some(flop_table("poker-levels-0.bin", 4)), None, None];
+    config.clusters = vec![169, 4, lossless(2), lossless(3)];
+    config.levels = vec![Level {
+        pot: Some(6000),
+        tables: vec![None, Some(flop_table("poker-levels-1.bin", 2)), None, None],
+        clusters: vec![169, 2, lossless(2), lossless(3)],
+    }];
+
+    let game = Poker::from_nodes(config, flop()).unwrap();
+
+    // the flop nodes before and after a bet
+    let size = game.size();
+    assert_eq!(size.len(), 169 + 2 * 4 + 2 * 2);
+
+    let mut rng = SmallRng::seed_from_u64(42);
+
+    for _ in 0..1000 {
+        let state = game.init(&mut rng);
+        let fresh = State::from(state.cards, state.board);
+
+        let mut node = game.root();
+        while !game.done(node) {
+            let pot = node.s[0] + node.s[1] + node.d;
+            assert_eq!(node.g, (pot >= 6000) as u8);
 
-        for row in 0..13 {
-            for col in 0..13 {
-                let s = &format!("{:.2} ", matrix[row][col]);
+            let t = node.t as usize;
+            let r = node.r as usize;
+            let bucket = state.buckets.clone().unwrap()[node.g as usize][t][r];
 
-                if matrix[row][col] > 0.5 {
-                    res += &s.green().to_string();
-                    continue;
-                }
+            let index = game.index(node, &state);
+            assert_eq!(game.index(node, &fresh), index);
+            assert_eq!(index, node.i + bucket);
+            assert!(bucket < [[169, 4], [169, 2]][node.g as usize][r]);
+            assert_eq!(size[index], game.next(node));
+
+            node = game.play(node, rng.gen_range(0..game.next(node)));
+        }
+    }
+}
+
+#[test]
+fn test_poker_explore() {
+    let game = Poker::limit("data/abstraction/".to_string()).unwrap();
 
-                if matrix[row][col] < 0.1 {
-                    res += &s.red().to_string();
-                    continue;
-                }
+    let mut rng = SmallRng::seed_from_u64(42);
 
-                res += s;
+    for _ in 0..100 {
+        let state = game.init(&mut rng);
+
+        let mut node = game.root();
+        while !game.done(node) {
+            let t = node.t as usize;
+            let r = node.r as usize;
+
+            let index = game.index(node, &state);
+            let bucket = game.explore(index, 3).unwrap();
+
+            assert_eq!(bucket.node.i, node.i);
+            assert_eq!(bucket.node.h, node.h);
+            assert_eq!(
+                bucket.buckets[r],
+                Some(state.buckets.clone().unwrap()[0][t][r])
+            );
+            assert!(!bucket.hands.is_empty());
+
+            for &(cards, board) in &bucket.hands {
+                let mut boards = [0; 4];
+                boards[r] = board;
+
+                assert_eq!(game.index(node, &State::from([cards; 2], boards)), index);
             }
-            res += "\n";
+
+            node = game.play(node, rng.gen_range(0..game.next(node)));
         }
+    }
 
-        res
-    };
+    assert!(game.explore(game.size().len(), 3).is_none());
+}
+
+#[test]
+fn test_poker_solve() {
+    let infosets = get(
+        &"tests/data/poker-sol.bin".to_string(),
+        Box::new(|| {
+            solve(
+                50000000,
+                42,
+                &Poker::new("data/abstraction/".to_string()).unwrap(),
+            )
+        }),
+    );
+
+    let game = Poker::new("data/abstraction/".to_string()).unwrap();
+
+    let node = game.root();
+    // let node = game.play(node, 1);
+    // let node = game.play(node, 1);
+
+    println!("{}: {}", node.h, node.i);
+
+    let chart = chart(&game, &infosets, node, "").unwrap();
 
     println!();
-    for (i, x) in matrix.into_iter().enumerate() {
-        println!("{:?}:\n{}", game.play(node, i).s, display(&x));
-    }
+    print!("{}", chart.terminal());
 }
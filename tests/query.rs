@@ -0,0 +1,57 @@
+use poker_solver::{
+    error::Error,
+    interfaces::game::Game,
+    poker::{
+        action::Action,
+        game::Poker,
+        nolimit::Table,
+        query::{query, walk},
+    },
+    solver::Infoset,
+};
+
+#[test]
+fn test_query() {
+    let game = Poker::nolimit("data/abstraction/".to_string(), Table::default()).unwrap();
+
+    let infosets = game
+        .size()
+        .into_iter()
+        .map(Infoset::new)
+        .collect::<Vec<Infoset>>();
+
+    let spot = query(&game, &infosets, "", "AhKd", "").unwrap();
+
+    assert_eq!(spot.player, 0);
+    assert_eq!(spot.pot, 3000);
+    assert_eq!(spot.committed, [1000, 2000]);
+    assert_eq!(spot.behind, Some([99000, 98000]));
+
+    assert_eq!(spot.choices[0].action, Action::Fold);
+    assert_eq!(spot.choices[1].action, Action::Call);
+    assert_eq!(spot.choices[1].amount, 1000);
+    assert_eq!(spot.choices[1].label(), "call 1000");
+
+    let total = spot.choices.iter().map(|x| x.probability).sum::<f64>();
+    assert!((total - 1.0).abs() < 1e-9);
+
+    let spot = query(&game, &infosets, "r4000c/", "AhKd", "2c7d9h").unwrap();
+
+    assert_eq!(spot.history, "r4000c/");
+    assert_eq!(spot.player, 1);
+    assert_eq!(spot.street, 1);
+    assert_eq!(spot.pot, 8000);
+    assert_eq!(spot.choices[0].action, Action::Check);
+
+    assert_eq!(walk(&game, "r4000c/").unwrap().h, "r4000c/");
+
+    assert!(walk(&game, "r4001").is_err());
+    assert!(query(&game, &infosets, "f", "AhKd", "").is_err());
+    assert!(query(&game, &infosets, "r4000c/", "AhKd", "").is_err());
+    assert!(query(&game, &infosets, "r4000c/", "Ah", "2c7d9h").is_err());
+    assert!(query(&game, &infosets, "r4000c/", "AhKd", "Ah7d9h").is_err());
+
+    // a solution of another game
+    let error = query(&game, &infosets[1..], "", "AhKd", "").err().unwrap();
+    assert!(matches!(error, Error::Config(_)));
+}
@@ -0,0 +1,76 @@
+use poker_solver::{
+    interfaces::game::Game,
+    poker::{
+        cards,
+        game::{Poker, State},
+        range::{self, Range, COMBOS},
+        reach::reach,
+    },
+    solver::Infoset,
+};
+
+#[test]
+fn test_reach() {
+    let game = Poker::limit("data/abstraction/".to_string()).unwrap();
+
+    let mut infosets = game
+        .size()
+        .into_iter()
+        .map(Infoset::new)
+        .collect::<Vec<Infoset>>();
+
+    let root = game.root();
+    let n = game.next(root) as f64;
+
+    let [a, b] = reach(&game, &infosets, "", "").unwrap();
+    assert!(a.w.iter().chain(&b.w).all(|&x| x == 1.0));
+
+    let [a, b] = reach(&game, &infosets, "cx/", "2c7d9h").unwrap();
+    let blocked = cards::parse("2c7d9h").unwrap();
+
+    for i in 0..COMBOS {
+        if range::combo(i) & blocked != 0 {
+            assert_eq!(a.w[i], 0.0);
+            assert_eq!(b.w[i], 0.0);
+        } else {
+            assert!((a.w[i] - 1.0 / n).abs() < 1e-9);
+            assert!((b.w[i] - 0.5).abs() < 1e-9);
+        }
+    }
+
+    assert!(reach(&game, &infosets, "cx/", "").is_err());
+    assert!(reach(&game, &infosets, "cx/x", "").is_err());
+    assert!(reach(&game, &infosets, "cx/x", "2c7d").is_err());
+    assert!(reach(&game, &infosets, "cx/x", "2c7d9h").is_ok());
+
+    // a hand that always raises reaches the raise and never the call
+    let hand = cards::parse("AhKd").unwrap();
+    let index = game.index(root, &State::from([hand, hand], [0; 4]));
+    infosets[index].s = vec![0.0, 0.0, 1.0];
+
+    let raise = game.play(root, 2).h.clone();
+
+    let [a, _] = reach(&game, &infosets, &raise, "").unwrap();
+    assert_eq!(a.w[range::index(hand)], 1.0);
+
+    let [a, _] = reach(&game, &infosets, "c", "").unwrap();
+    assert_eq!(a.w[range::index(hand)], 0.0);
+    assert!(a.w.iter().any(|&x| x > 0.0));
+
+    // the button is only dealt aces or kings
+    let kings = cards::parse("KcKh").unwrap();
+
+    let mut opening = vec![0.0; COMBOS];
+    opening[range::index(hand)] = 3.0;
+    opening[range::index(kings)] = 1.0;
+
+    let game = game
+        .with_ranges([Range::new(opening), Range::uniform()])
+        .unwrap();
+
+    let [a, b] = reach(&game, &infosets, &raise, "").unwrap();
+    assert_eq!(a.w[range::index(hand)], 3.0);
+    assert_eq!(a.w[range::index(kings)], 1.0 / n);
+    assert_eq!(a.w.iter().filter(|&&x| x > 0.0).count(), 2);
+    assert!(b.w.iter().all(|&x| x == 1.0));
+}
@@ -0,0 +1,102 @@
+use rand::prelude::*;
+
+use poker_indexer::Indexer;
+use poker_solver::{
+    error::Error,
+    poker::{
+        cards,
+        config::PokerConfig,
+        game::{Clusters, Deal, Variant},
+        shortdeck,
+    },
+};
+
+fn evaluate(input: &str) -> u32 {
+    shortdeck::evaluate(cards::parse(input).unwrap())
+}
+
+#[test]
+fn test_ranking() {
+    // A-6-7-8-9 is the lowest straight
+    assert!(evaluate("As6d7c8h9s") > evaluate("AsAdAc8h9s"));
+    assert!(evaluate("As6d7c8h9s") < evaluate("6d7c8h9sTs"));
+    assert!(evaluate("KsKdKcKh9s") > evaluate("As6s7s8sTs"));
+
+    // a flush beats a full house
+    assert!(evaluate("As6s7s8sJs") > evaluate("AsAdAcKhKs"));
+    assert!(evaluate("AsAdAcKhKs") > evaluate("AsKdQcJhTs"));
+
+    assert!(evaluate("As6s7s8s9s") > evaluate("AsAdAcAhKs"));
+    assert!(evaluate("TsJsQsKsAs") > evaluate("6s7s8s9sTs"));
+
+    // best five of seven
+    assert_eq!(evaluate("AsAd KcKh QsQd Jc"), evaluate("AsAd KcKh Qc"));
+    assert!(evaluate("AsAd KcKh QsQd Jc") > evaluate("AsAd KcKh JsJd Tc"));
+    assert_eq!(evaluate("AsAdAc KhKsKd 9c"), evaluate("AsAdAc KhKs"));
+    assert!(evaluate("9s9d 6c7h8sTd Ah") > evaluate("9s9d 6c7h8sJd Ah"));
+}
+
+#[test]
+fn test_deal() {
+    let mut rng = SmallRng::seed_from_u64(42);
+
+    for _ in 0..100 {
+        let state = Deal::new()
+            .variant(Variant::ShortDeck)
+            .build(&mut rng)
+            .unwrap();
+
+        assert_eq!((state.cards[0] | state.cards[1]) & !shortdeck::DECK, 0);
+        assert_eq!(state.board[3] & !shortdeck::DECK, 0);
+        assert_eq!(state.board[3].count_ones(), 5);
+    }
+
+    assert_eq!(shortdeck::DECK.count_ones(), 36);
+
+    assert!(Deal::parse(["As5d", ""], "")
+        .unwrap()
+        .variant(Variant::ShortDeck)
+        .check()
+        .is_err());
+}
+
+#[test]
+fn test_tables() {
+    let config = PokerConfig::shortdeck("data/abstraction/".to_string());
+
+    assert_eq!(
+        config.tables[1],
+        Some("data/abstraction/shortdeck_cluster_1.bin".to_string())
+    );
+
+    let indexer = Indexer::new(vec![2, 3]);
+    let count = indexer.count[1];
+
+    // flop clusters in turn, and only for the hands short deck deals
+    let write = |name: &str, short: bool| {
+        let mut data = count.to_le_bytes().to_vec();
+        for i in 0..count {
+            let x = indexer.unindex(i, 1);
+            let dealt = (x[0] | x[1]) & !shortdeck::DECK == 0;
+
+            let bucket = if short && !dealt { 0 } else { i % 4 };
+            data.extend((bucket as u16).to_le_bytes());
+        }
+
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, data).unwrap();
+
+        path.to_str().unwrap().to_string()
+    };
+
+    let lossless = |shape: Vec<usize>| Indexer::new(shape).count[1] as usize;
+
+    let mut config = config;
+    config.clusters = vec![169, 4, lossless(vec![2, 4]), lossless(vec![2, 5])];
+
+    config.tables = vec![None, Some(write("shortdeck-flop.bin", true)), None, None];
+    assert!(Clusters::new(&config).is_ok());
+
+    config.tables = vec![None, Some(write("holdem-flop.bin", false)), None, None];
+    assert!(matches!(Clusters::new(&config), Err(Error::Config(_))));
+}
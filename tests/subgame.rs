@@ -0,0 +1,234 @@
+use rand::prelude::*;
+
+use poker_abstraction::tables::get;
+
+use poker_solver::{
+    error::Error,
+    interfaces::game::Game,
+    poker::{
+        cards,
+        config::PokerConfig,
+        game::{Node, Poker, State, Variant},
+        limit::Limit,
+        nolimit::Table,
+        range::{self, Range, Sampler, COMBOS},
+        subgame::{Abstraction, Subgame},
+    },
+    solver::{normalize, solve},
+};
+
+#[test]
+fn test_combos() {
+    let mut seen = vec![false; COMBOS];
+
+    for a in 0..52 {
+        for b in 0..a {
+            let cards = 1 << a | 1 << b;
+
+            let i = range::index(cards);
+
+            assert!(!seen[i]);
+            seen[i] = true;
+
+            assert_eq!(range::combo(i), cards);
+        }
+    }
+}
+
+#[test]
+fn test_sampler() {
+    let mut w = vec![0.0; COMBOS];
+    w[range::index(1 << 12 | 1 << 25)] = 1.0;
+    w[range::index(1 << 12 | 1 << 38)] = 1.0;
+    w[range::index(1 << 11 | 1 << 24)] = 1.0;
+
+    let ranges = [Range::new(w), Range::uniform()];
+
+    let board = 1 << 38 | 1 << 0 | 1 << 1;
+
+    let sampler = Sampler::new(&ranges, board).unwrap();
+
+    let mut rng = SmallRng::seed_from_u64(42);
+    for _ in 0..1000 {
+        let [a, b] = sampler.sample(&mut rng);
+
+        assert_eq!(a & b, 0);
+        assert_eq!((a | b) & board, 0);
+        assert!(a == 1 << 12 | 1 << 25 || a == 1 << 11 | 1 << 24);
+    }
+}
+
+#[test]
+fn test_sampler_errors() {
+    let mut w = vec![0.0; COMBOS];
+    w[range::index(1 << 12 | 1 << 25)] = 1.0;
+
+    // both players can only hold the same two cards
+    let ranges = [Range::new(w.clone()), Range::new(w)];
+    assert!(Sampler::new(&ranges, 0).is_err());
+
+    assert!(Sampler::new(&[Range::new(vec![0.0; COMBOS]), Range::uniform()], 0).is_err());
+
+    // a pair of fives, which short deck removes
+    let mut w = vec![0.0; COMBOS];
+    w[range::index(1 << 3 | 1 << 16)] = 1.0;
+    let short = cards::DECK & !Variant::ShortDeck.deck();
+    assert!(Sampler::new(&[Range::new(w.clone()), Range::uniform()], 0).is_ok());
+    assert!(Sampler::new(&[Range::new(w), Range::uniform()], short).is_err());
+
+    let mut w = vec![1.0; COMBOS];
+    w[0] = -1.0;
+    assert!(Sampler::new(&[Range::new(w), Range::uniform()], 0).is_err());
+
+    assert!(serde_json::from_str::<Range>("{\"w\": [1.0, 2.0]}").is_err());
+
+    let json = serde_json::to_string(&Range::uniform()).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Range>(&json).unwrap().w.len(),
+        COMBOS
+    );
+}
+
+#[test]
+fn test_subgame_errors() {
+    let config = || PokerConfig::new("data/abstraction/".to_string());
+    let ranges = || [Range::uniform(), Range::uniform()];
+
+    let error = |nodes: Vec<Node>| {
+        matches!(
+            Subgame::new(config(), nodes, [0; 4], ranges(), Abstraction::Lossless),
+            Err(Error::Config(_))
+        )
+    };
+
+    assert!(error(Vec::new()));
+
+    // the root of a whole game is a preflop node
+    assert!(error(Limit::default().build()));
+
+    // a flop subgame would be far too large to index losslessly
+    assert!(matches!(
+        Subgame::postflop(
+            config(),
+            Table::default(),
+            cards::board("Th9h2c").unwrap(),
+            [10000, 10000],
+            ranges(),
+        ),
+        Err(Error::Config(_))
+    ));
+
+    let mut bad = config();
+    bad.shapes.pop();
+    assert!(matches!(
+        Subgame::new(
+            bad,
+            Limit::default().build(),
+            [0; 4],
+            ranges(),
+            Abstraction::Lossless
+        ),
+        Err(Error::Config(_))
+    ));
+}
+
+#[test]
+fn test_subgame_solve() {
+    let game = Poker::new("data/abstraction/".to_string()).unwrap();
+
+    let blueprint = get(
+        &"tests/data/poker-sol.bin".to_string(),
+        Box::new(|| {
+            solve(
+                50000000,
+                42,
+                &Poker::new("data/abstraction/".to_string()).unwrap(),
+            )
+        }),
+    );
+
+    // checked through the flop to the turn
+    let node = game.play(game.root(), 1);
+    let node = game.play(node, 1);
+    let node = game.play(node, 0);
+    let node = game.play(node, 0);
+    assert_eq!(node.r, 2);
+
+    let flop = 1 << 6 | 1 << 3 | 1 << 34;
+    let board = [0, flop, flop | 1 << 8, flop | 1 << 8 | 1 << 39];
+
+    let subgame = Subgame::new(
+        PokerConfig::new("data/abstraction/".to_string()),
+        game.subtree(node),
+        [0, flop, flop | 1 << 8, 0],
+        [Range::uniform(), Range::uniform()],
+        Abstraction::Lossless,
+    )
+    .unwrap();
+
+    assert_eq!(subgame.root().s, node.s);
+
+    let infosets = solve(1000000, 42, &subgame);
+
+    let root = subgame.root();
+
+    // the blueprint plays every hand of a cluster alike, the subgame does not
+    let mut changed = false;
+    for cards in [1 << 12 | 1 << 25, 1 << 5 | 1 << 18, 1 << 0 | 1 << 14] {
+        let state = State::from([cards, cards], board);
+
+        let strategy = normalize(infosets[subgame.index(root, &state)].s.clone());
+        let before = normalize(blueprint[game.index(node, &state)].s.clone());
+
+        assert_eq!(strategy.len(), before.len());
+        assert!((strategy.iter().sum::<f64>() - 1.0).abs() < 1e-6);
+
+        changed |= strategy
+            .iter()
+            .zip(&before)
+            .any(|(a, b)| (a - b).abs() > 0.05);
+    }
+
+    assert!(changed);
+}
+
+#[test]
+fn test_postflop_solve() {
+    let board = cards::board("Th9h2cJd5s").unwrap();
+
+    let game = Subgame::postflop(
+        PokerConfig::new("data/abstraction/".to_string()),
+        Table::default(),
+        board,
+        [10000, 10000],
+        [Range::uniform(), Range::uniform()],
+    )
+    .unwrap();
+
+    for size in game.size() {
+        assert!(size >= 2);
+    }
+
+    let infosets = solve(1000000, 42, &game);
+
+    let node = game.root();
+    let strategies = game.strategies(&infosets, node);
+
+    // every hand the five board cards leave
+    assert_eq!(strategies.len(), 47 * 46 / 2);
+
+    let mut nuts = Vec::new();
+    for (cards, strategy) in strategies {
+        assert_eq!(strategy.len(), game.next(node));
+        assert!((strategy.iter().sum::<f64>() - 1.0).abs() < 1e-6);
+
+        let ranks = cards::format(cards, true);
+        if ranks.contains('K') && ranks.contains('Q') {
+            nuts.push(strategy[0]);
+        }
+    }
+
+    // king queen makes the nut straight and should rarely check
+    assert_eq!(nuts.len(), 16);
+    assert!(nuts.iter().sum::<f64>() / (nuts.len() as f64) < 0.5);
+}
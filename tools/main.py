@@ -149,8 +149,56 @@ def dfs(k, r, action, history, s0, s1, amount):
     return add(k, r, action, history, s0, s1, children)
 
 
+def typed(parent, child):
+    t = parent["t"]
+
+    if child["a"] == "f":
+        return "Fold"
+
+    if child["a"] == "c":
+        return "Call" if parent["s"][t] < parent["s"][1 - t] else "Check"
+
+    # amounts are the total put in once the action is taken
+    if child["a"] == "b":
+        return {"Bet": child["s"][t]}
+
+    if child["a"] == "x":
+        return {"Raise": child["s"][t]}
+
+    return {"AllIn": child["s"][t]}
+
+
+def notation(action):
+    if isinstance(action, dict):
+        (kind, amount), = action.items()
+
+        return {"Bet": "b", "Raise": "r", "AllIn": "a"}[kind] + str(amount)
+
+    return {"Fold": "f", "Check": "x", "Call": "c"}[action]
+
+
+def convert(i, action, history):
+    node = nodes[i]
+
+    for c in node["c"]:
+        child = nodes[c]
+
+        a = typed(node, child)
+        h = history + notation(a)
+
+        if child["r"] != node["r"] and child["r"] <= 3:
+            h += "/"
+
+        convert(c, a, h)
+
+    node["a"] = action
+    node["h"] = history
+
+
 dfs(0, 0, ".", "", BLIND, BLIND + BLIND, BLIND)
 
+convert(len(nodes) - 1, None, "")
+
 index = 0
 
 for i in range(len(nodes)):